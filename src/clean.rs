@@ -0,0 +1,90 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::{config::cache_dir, core::metadata::CloneRegistry, prompt::confirm};
+
+/// removes the cloned task repositories and/or the cache directory after showing what would be removed
+pub fn run_clean(repos: bool, cache: bool, force: bool) -> Result<()> {
+    // without a selection everything is cleaned
+    let (repos, cache) = if !repos && !cache { (true, true) } else { (repos, cache) };
+
+    let mut registry = CloneRegistry::load()?;
+    let mut targets: Vec<PathBuf> = Vec::new();
+    if repos {
+        targets.extend(registry.clones().iter().map(|clone| clone.path.clone()).filter(|path| path.exists()));
+    }
+    if cache && cache_dir().exists() {
+        targets.push(cache_dir());
+    }
+
+    if targets.is_empty() {
+        println!("nothing to clean");
+        return Ok(());
+    }
+
+    let mut total = 0;
+    for target in &targets {
+        let size = dir_size(target);
+        total += size;
+        println!("{:>10} {}", format_size(size), target.display());
+    }
+
+    if !force && !confirm(&format!("delete these {} directories?", targets.len()))? {
+        println!("nothing was removed, run with --force to delete without asking");
+        return Ok(());
+    }
+
+    for target in &targets {
+        fs::remove_dir_all(target)?;
+        registry.remove(target);
+    }
+    registry.save()?;
+    println!("freed {}", format_size(total));
+    Ok(())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
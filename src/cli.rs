@@ -15,9 +15,18 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use clap::{Parser, Subcommand, command};
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::{
+    core::{
+        adapter::{AuthMode, CloneProtocol, ParticipationType},
+        git::DEFAULT_REMOTE,
+    },
+    credentials::CredentialStore,
+    output::{TaskSort, TestSort},
+};
+
 #[derive(Parser, Debug, Clone)]
 #[command(name = "artemiscli")]
 #[command(about = "A CLI tool for intercating with artemis tasks")]
@@ -29,6 +38,54 @@ pub(crate) struct Cli {
     #[arg(short, long)]
     pub(crate) cfg: Option<PathBuf>,
 
+    /// Use this Artemis instance for this invocation only instead of the configured one
+    #[arg(long, global = true)]
+    pub(crate) base_url: Option<String>,
+
+    /// Use the task repository at this path instead of the current directory
+    #[arg(long, global = true, value_name = "PATH")]
+    pub(crate) repo_dir: Option<PathBuf>,
+
+    /// Print results as JSON instead of plain text
+    #[arg(long, global = true)]
+    pub(crate) json: bool,
+
+    /// Only print a summary of the results
+    #[arg(short, long, global = true)]
+    pub(crate) quiet: bool,
+
+    /// Only print the tests that failed
+    #[arg(long, global = true)]
+    pub(crate) only_failed: bool,
+
+    /// Order of the printed tests, by default they are printed in the order artemis returns them
+    #[arg(long, global = true, value_enum)]
+    pub(crate) sort_tests: Option<TestSort>,
+
+    /// Width of the tables in characters, by default the width of the terminal
+    #[arg(long, global = true)]
+    pub(crate) width: Option<usize>,
+
+    /// Timeout in seconds for this run only, takes precedence over the configured timeout
+    #[arg(long, global = true, value_name = "SECS")]
+    pub(crate) timeout_override: Option<u64>,
+
+    /// Ignore the stored login and remember-me tokens and log in with your password, the new token replaces the old one
+    #[arg(long, global = true)]
+    pub(crate) fresh_login: bool,
+
+    /// Don't show test results that are longer than the terminal in $PAGER
+    #[arg(long, global = true)]
+    pub(crate) no_pager: bool,
+
+    /// Answer every confirmation with yes without asking, the questions are still printed
+    #[arg(short, long, global = true)]
+    pub(crate) yes: bool,
+
+    /// Don't color the output, also disabled by setting NO_COLOR
+    #[arg(long, global = true)]
+    pub(crate) no_color: bool,
+
     #[command(subcommand)]
     pub(crate) command: Option<Commands>,
 }
@@ -36,24 +93,261 @@ pub(crate) struct Cli {
 #[derive(Subcommand, Debug, Clone)]
 pub(crate) enum Commands {
     /// lists all enrolled courses on artemis
-    ListCourses,
+    ListCourses {
+        /// only print the total number of courses, exercises, completed and overdue exercises
+        #[arg(long)]
+        count: bool,
+        /// only list courses of this semester, e.g. "WS24"
+        #[arg(long)]
+        semester: Option<String>,
+    },
     /// lists all available tasks on artemis
     ListTasks {
         /// the id of the course as shown by list-courses
         courseid: u64,
+        /// only print the first N tasks
+        #[arg(short, long)]
+        limit: Option<usize>,
+        /// only print tasks of this category, e.g. "Homework"
+        #[arg(long)]
+        category: Option<String>,
+        /// also list tasks that haven't been released yet
+        #[arg(long)]
+        include_unreleased: bool,
+        /// order the tasks by difficulty or by points per difficulty
+        #[arg(long, value_enum)]
+        sort: Option<TaskSort>,
+        /// refresh the list every SECS seconds until interrupted with Ctrl-C
+        #[arg(short, long, value_name = "SECS")]
+        watch: Option<u64>,
+        /// print one dense line per task, e.g. `[✓] 1234 Title (80%, due 3d)`
+        #[arg(long)]
+        compact: bool,
+    },
+    /// prints the incomplete task of a course that is due next
+    Next {
+        /// the id of the course as shown by list-courses
+        courseid: u64,
+    },
+    /// watches all courses and sends desktop notifications for tasks due soon and new results
+    Watch {
+        /// check every SECS seconds
+        #[arg(short, long, value_name = "SECS", default_value_t = 300)]
+        interval: u64,
+        /// notify about tasks due within this many hours
+        #[arg(long, value_name = "HOURS", default_value_t = 24)]
+        due_soon: u64,
+    },
+    /// interactive overview of all courses and their tasks, from which tasks can be started and submitted
+    Dashboard,
+    /// shows the progress towards the competencies of a course
+    Competencies {
+        /// the id of the course as shown by list-courses
+        courseid: u64,
+    },
+    /// prints the latest announcements of a course. artemis doesn't tell which ones you have read, so the
+    /// latest ones are shown instead
+    Announcements {
+        /// the id of the course as shown by list-courses
+        courseid: u64,
+        /// how many announcements to print
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
+    },
+    /// shows the score of every task of a course and the total score of the course
+    Grades {
+        /// the id of the course as shown by list-courses
+        courseid: u64,
+    },
+    /// shows the used and remaining submissions of every task with a submission policy
+    Attempts {
+        /// the id of the course as shown by list-courses
+        courseid: u64,
     },
     /// start artemis task and clone the gl repository
     StartTask {
+        /// the ids of the tasks as given by list-task, each is cloned into its own directory
+        #[arg(required = true)]
+        taskids: Vec<u64>,
+        /// start a practice participation that doesn't affect the graded one
+        #[arg(long, value_enum, default_value_t)]
+        participation_type: ParticipationType,
+        /// clone into this directory instead of the one given by the clone-dir-template config, only for a single task
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// clone the auxiliary repository with this name instead of your own one, e.g. for additional tests
+        #[arg(long, value_name = "NAME")]
+        template: Option<String>,
+    },
+    /// clones the example solution of a task or prints it, once it has been released
+    Solution {
+        /// the id of the task as given by list-task
+        taskid: u64,
+        /// clone into this directory instead of `artemis-task-nr-[TASK ID]-solution`
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// prints the build logs of the latest build of a task
+    Logs {
+        /// the id of the task as given by list-task
+        taskid: u64,
+        /// keep printing new log lines until the running build completes, like tail -f
+        #[arg(short, long)]
+        follow: bool,
+        #[arg(long, value_enum, default_value_t)]
+        participation_type: ParticipationType,
+    },
+    /// lists the members and the shared repository of your team for a team exercise
+    Team {
+        /// the id of the task as given by list-task
+        taskid: u64,
+    },
+    /// prints the artemis account you are logged in as, exits with an error if the login fails
+    Whoami,
+    /// prints the hints of a task that are available to you
+    Hints {
         /// the id of the task as given by list-task
         taskid: u64,
     },
     /// creates a commit, pushes to the repo and returns the test results
-    Submit,
+    Submit {
+        /// additionally write the test results to the given file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// open the repository in the configured editor if a test fails or the build breaks
+        #[arg(long)]
+        open_on_fail: bool,
+        /// show the changes and ask for confirmation before committing and pushing them
+        #[arg(long)]
+        diff_only: bool,
+        /// only commit and push, fetch the results later with 'fetch'
+        #[arg(long, conflicts_with_all = ["output", "open_on_fail"])]
+        no_fetch: bool,
+        /// don't push again, wait for the result of the last submission that is still awaiting it
+        #[arg(long, conflicts_with_all = ["diff_only", "no_fetch"])]
+        resume: bool,
+        /// the commit message, given multiple times the messages are joined as separate paragraphs
+        #[arg(short, long, conflicts_with = "resume")]
+        message: Vec<String>,
+        /// read the commit message from a file, the first line is the subject
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["message", "resume"])]
+        message_file: Option<PathBuf>,
+        /// push again up to N times while the build fails because of the build agent, e.g. a runner timeout
+        #[arg(long, value_name = "N", default_value_t = 0, conflicts_with = "no_fetch")]
+        retry_build: u32,
+        /// push to this remote instead of origin, e.g. a personal backup
+        #[arg(long, value_name = "NAME", default_value = DEFAULT_REMOTE, conflicts_with = "resume")]
+        remote: String,
+        /// tag the submitted commit and push the tag, named submission-N for the N-th submission if no name is given
+        #[arg(long, value_name = "NAME", num_args = 0..=1, default_missing_value = "", conflicts_with = "resume")]
+        tag: Option<String>,
+        /// don't read the task metadata of the repository or look up the participation, for scripts that submit
+        /// often. needs --task-id and --participation-id
+        #[arg(long, requires_all = ["task_id", "participation_id"], conflicts_with = "resume")]
+        assume_cloned: bool,
+        /// the id of the task the repository belongs to, used with --assume-cloned
+        #[arg(long, value_name = "ID", requires = "assume_cloned")]
+        task_id: Option<u64>,
+        /// the id of the participation the results are fetched from, used with --assume-cloned
+        #[arg(long, value_name = "ID", requires = "assume_cloned")]
+        participation_id: Option<u64>,
+        /// give up waiting for the result after SECS seconds, the exit code is 4 then
+        #[arg(long, value_name = "SECS", default_value_t = 120, conflicts_with = "no_fetch")]
+        wait_timeout: u64,
+        /// commit as this author instead of the one from the git config, e.g. "Jane Doe <jane@example.com>"
+        #[arg(long, value_name = "AUTHOR")]
+        commit_author: Option<String>,
+    },
+    /// shows the changes of the working tree since the last commit or an earlier submission
+    Diff {
+        /// compare against this commit instead of HEAD
+        #[arg(long, value_name = "SHA", conflicts_with = "since_submission")]
+        since_commit: Option<String>,
+        /// compare against the N-th submission, counted from the first commit (0 is the initial template)
+        #[arg(long, value_name = "N")]
+        since_submission: Option<usize>,
+    },
+    /// fetches updates of the task repository, e.g. changes pushed by the instructors
+    Pull {
+        /// replay local commits on top of the remote ones if the branches diverged
+        #[arg(long)]
+        rebase: bool,
+        /// pull from this remote instead of origin
+        #[arg(long, value_name = "NAME", default_value = DEFAULT_REMOTE)]
+        remote: String,
+    },
+    /// uploads a file as submission for a file upload exercise
+    SubmitFile {
+        /// the id of the task as given by list-task
+        taskid: u64,
+        /// the file to upload
+        file: PathBuf,
+    },
     /// fetches and prints the test results
     Fetch {
         /// the id of the task as given by list-task
         taskid: u64,
+        /// additionally write the test results to the given file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// which participation to fetch the results of
+        #[arg(long, value_enum, default_value_t)]
+        participation_type: ParticipationType,
+        /// also print textual feedback, e.g. from a tutor, as guidance
+        #[arg(long)]
+        explain: bool,
+        /// show the result of the N-th submission (1 is the first) instead of the latest
+        #[arg(long, value_name = "N")]
+        attempt: Option<usize>,
+        /// fetch the results of this participation directly instead of looking it up in the exercise details
+        #[arg(long, value_name = "ID", conflicts_with = "attempt")]
+        participation_id: Option<u64>,
+        /// show this result of the participation instead of the latest
+        #[arg(long, value_name = "ID", requires = "participation_id")]
+        result_id: Option<u64>,
+        /// download the result details again instead of using the cached ones
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// prints all results of a task, oldest first
+    TestHistory {
+        /// the id of the task as given by list-task
+        taskid: u64,
+        /// print one JSON object per result instead of a table
+        #[arg(long)]
+        jsonl: bool,
+        /// which participation to print the results of
+        #[arg(long, value_enum, default_value_t)]
+        participation_type: ParticipationType,
     },
+    /// prints the most recent commands, a command can be run again with --replay
+    History {
+        /// how many commands to print
+        #[arg(short, long, default_value_t = 20)]
+        count: usize,
+        /// run the command with this number again, 1 is the most recent one
+        #[arg(long, value_name = "INDEX")]
+        replay: Option<usize>,
+    },
+    /// prints JSON schemas of the output of --json
+    #[command(hide = true)]
+    Schema,
+    /// removes cloned task repositories and cached data
+    Clean {
+        /// only remove the cloned task repositories
+        #[arg(long)]
+        repos: bool,
+        /// only remove the cache
+        #[arg(long)]
+        cache: bool,
+        /// delete without asking for confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// guides through the first time setup of base url, username and password
+    Init,
+    /// checks the environment for common setup problems
+    Doctor,
     /// sets the global configuration for login data
     Config {
         #[command(subcommand)]
@@ -65,5 +359,53 @@ pub(crate) enum Commands {
 pub(crate) enum ConfigCommands {
     Username { name: String },
     Password { password: String },
-    BaseUrl { url: String },
+    BaseUrl {
+        #[arg(required_unless_present = "university")]
+        url: Option<String>,
+        /// use the artemis instance of a known university instead of giving its url, e.g. tud or tum
+        #[arg(long, conflicts_with = "url")]
+        university: Option<String>,
+    },
+    UserAgent { agent: String },
+    /// path of the login endpoint, e.g. /api/authenticate for older artemis versions
+    AuthPath { path: String },
+    Editor { command: String },
+    /// default verbosity when no -v flags are given (0 = off ... 5 = trace)
+    Verbosity { level: u8 },
+    /// timeout of requests to artemis and of git network operations in seconds
+    Timeout { seconds: u64 },
+    /// where the username, password and login tokens are kept
+    CredentialStore {
+        #[arg(value_enum)]
+        store: CredentialStore,
+    },
+    /// send the login as session cookie or as bearer token, for deployments behind proxies that strip cookies
+    AuthMode {
+        #[arg(value_enum)]
+        mode: AuthMode,
+    },
+    /// clone repositories artemis serves over https with the session (https) or with your ssh keys (ssh)
+    CloneProtocol {
+        #[arg(value_enum)]
+        protocol: CloneProtocol,
+    },
+    /// directory start-task clones into, may contain {course_id}, {course_title}, {task_id} and {task_title}
+    CloneDirTemplate { template: String },
+    /// author of submissions as "Name <email>", used instead of the git config, e.g. on shared lab machines
+    CommitAuthor { author: String },
+    /// how many hours the cached mapping of tasks to their courses is used before it is fetched again
+    TaskCacheMaxAge { hours: u64 },
+    /// how timestamps are shown: 24h, 12h, relative or a strftime pattern such as "%d.%m.%Y %H:%M"
+    TimeFormat { format: String },
+    /// whether the login token is kept in the keyring between invocations
+    RememberMe {
+        #[arg(action = clap::ArgAction::Set)]
+        enabled: bool,
+    },
+    /// prints all settings
+    Show,
+    /// resets a setting to its default value
+    Unset { key: String },
+    /// upgrades the config file to the current format, filling in defaults and dropping invalid settings
+    Migrate,
 }
@@ -15,33 +15,217 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
+use anyhow::{Result, anyhow, bail};
+use chrono::TimeDelta;
 use log::warn;
+use reqwest::{Url, header::HeaderValue};
 use serde::{Deserialize, Serialize};
 use std::{
     env,
     fs::{self, OpenOptions},
     io::{Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    core::{
+        adapter::{AuthMode, CloneProtocol},
+        git::Identity,
+    },
+    credentials::CredentialStore,
+    time_format::TimeFormat,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct ArtemisConfig {
     base_url: String,
+    #[serde(default = "default_user_agent")]
+    user_agent: String,
+    /// path of the login endpoint relative to `base_url`, older instances use `/api/authenticate`
+    #[serde(default = "default_auth_path")]
+    auth_path: String,
+    /// command used to open task repositories, falls back to $VISUAL and $EDITOR
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    editor: Option<String>,
+    /// log verbosity used when no -v flags are given, same scale as the number of -v flags
+    #[serde(default)]
+    verbosity: u8,
+    /// keep the login token in the keyring between invocations
+    #[serde(default = "default_remember_me")]
+    remember_me: bool,
+    /// where the username, password and login tokens are kept
+    #[serde(default)]
+    credential_store: CredentialStore,
+    /// whether the login is sent as session cookie or as bearer token
+    #[serde(default)]
+    auth_mode: AuthMode,
+    /// whether repositories served over https are cloned over https or ssh
+    #[serde(default)]
+    clone_protocol: CloneProtocol,
+    /// directory start-task clones into, e.g. `~/artemis/{course_title}/{task_title}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    clone_dir_template: Option<String>,
+    /// author of submissions as `Name <email>`, takes precedence over the git config, e.g. on shared lab machines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit_author: Option<String>,
+    /// timeout of requests to artemis and of git network operations in seconds
+    #[serde(default = "default_timeout")]
+    timeout: u64,
+    /// hours the cached mapping of task ids to their courses is used, see `TaskIndex`
+    #[serde(default = "default_task_cache_max_age")]
+    task_cache_max_age: u64,
+    /// how timestamps are rendered, 12h, 24h, relative or a strftime pattern, see `TimeFormat`
+    #[serde(default = "default_time_format")]
+    time_format: String,
+    /// base url given on the command line, takes precedence over `base_url` and is never saved
+    #[serde(skip)]
+    base_url_override: Option<String>,
+    /// timeout given on the command line, takes precedence over `timeout` and is never saved
+    #[serde(skip)]
+    timeout_override: Option<u64>,
+    /// commit author given on the command line, takes precedence over `commit_author` and is never saved
+    #[serde(skip)]
+    commit_author_override: Option<String>,
+    /// ignore the stored login token for this invocation, given on the command line and never saved
+    #[serde(skip)]
+    fresh_login: bool,
+}
+
+/// directory for cached server responses, safe to delete at any time
+pub fn cache_dir() -> PathBuf {
+    let mut dir = env::home_dir().expect("cant get HOME directory");
+    dir.push(".cache/artemis-cli");
+    dir
+}
+
+/// directory for state that isn't configuration, e.g. the list of cloned tasks
+pub fn data_dir() -> PathBuf {
+    let mut dir = env::home_dir().expect("cant get HOME directory");
+    dir.push(".local/share/artemis-cli");
+    dir
+}
+
+/// the keys accepted by `config unset`
+const CONFIG_KEYS: [&str; 14] = [
+    "base-url",
+    "user-agent",
+    "auth-path",
+    "editor",
+    "verbosity",
+    "remember-me",
+    "credential-store",
+    "auth-mode",
+    "clone-protocol",
+    "clone-dir-template",
+    "commit-author",
+    "timeout",
+    "task-cache-max-age",
+    "time-format",
+];
+
+/// the placeholders `clone_dir_template` may contain
+pub const CLONE_DIR_PLACEHOLDERS: [&str; 4] = ["{course_id}", "{course_title}", "{task_id}", "{task_title}"];
+
+/// shown by `config show` below the remember-me setting
+const REMEMBER_ME_NOTE: &str = "keeps the login token in your keyring, anyone who can read the keyring can use it \
+until it expires. set it to false on shared machines to log in on every invocation instead";
+
+/// artemis instances that can be selected by a short key instead of their url
+const UNIVERSITIES: [(&str, &str, &str); 5] = [
+    ("tud", "TU Dresden", "https://artemis-app.inf.tu-dresden.de"),
+    ("tum", "TU München", "https://artemis.cit.tum.de"),
+    ("kit", "Karlsruher Institut für Technologie", "https://artemis.praktomat.cs.kit.edu"),
+    ("hm", "Hochschule München", "https://artemis.cs.hm.edu"),
+    ("jku", "Johannes Kepler Universität Linz", "https://artemis.jku.at"),
+];
+
+/// the base url of the university with the given key, unknown keys list the known ones
+pub fn university_base_url(key: &str) -> Result<&'static str> {
+    UNIVERSITIES
+        .iter()
+        .find(|(university, _, _)| university.eq_ignore_ascii_case(key.trim()))
+        .map(|(_, _, url)| *url)
+        .ok_or_else(|| {
+            let known: Vec<String> =
+                UNIVERSITIES.iter().map(|(key, name, _)| format!("{} ({})", key, name)).collect();
+            anyhow!("unknown university '{}', known ones are: {}", key, known.join(", "))
+        })
+}
+
+/// checks that `url` is an absolute http(s) url and strips trailing slashes
+fn normalize_base_url(url: &str) -> Result<String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| anyhow!("'{}' is not a valid url: {}", url, e))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        bail!("'{}' is not a http(s) url", url);
+    }
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
+
+fn default_user_agent() -> String {
+    format!("artemis-cli/{}", env!("CARGO_PKG_VERSION"))
+}
+
+fn default_remember_me() -> bool {
+    true
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+fn default_task_cache_max_age() -> u64 {
+    24
+}
+
+fn default_time_format() -> String {
+    TimeFormat::default().to_string()
+}
+
+fn default_auth_path() -> String {
+    "/api/public/authenticate".to_string()
 }
 
 impl Default for ArtemisConfig {
     fn default() -> Self {
         Self {
             base_url: "https://artemis-app.inf.tu-dresden.de".to_string(),
+            user_agent: default_user_agent(),
+            auth_path: default_auth_path(),
+            editor: None,
+            verbosity: 0,
+            remember_me: default_remember_me(),
+            credential_store: CredentialStore::default(),
+            auth_mode: AuthMode::default(),
+            clone_protocol: CloneProtocol::default(),
+            clone_dir_template: None,
+            commit_author: None,
+            timeout: default_timeout(),
+            task_cache_max_age: default_task_cache_max_age(),
+            time_format: default_time_format(),
+            base_url_override: None,
+            timeout_override: None,
+            commit_author_override: None,
+            fresh_login: false,
         }
     }
 }
 
 impl ArtemisConfig {
+    /// the config file given with --cfg or `~/.config/artemis-cli/config.toml`
+    pub fn path(path: Option<&Path>) -> PathBuf {
+        match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let mut home = env::home_dir().expect("cant get HOME directory");
+                home.push(".config/artemis-cli/config.toml");
+                home
+            }
+        }
+    }
+
     pub fn load(path: Option<&Path>) -> Self {
-        let mut home = env::home_dir().expect("cant get HOME directory");
-        home.push(".config/artemis-cli/config.toml");
-        let cfg_path = path.unwrap_or(&home);
+        let cfg_path = &Self::path(path);
 
         if let Some(parent) = cfg_path.parent() {
             fs::create_dir_all(parent).unwrap();
@@ -66,13 +250,52 @@ impl ArtemisConfig {
         let mut buf = String::new();
         cfg_file.read_to_string(&mut buf).expect("cant read cfg file");
 
-        toml::from_str::<ArtemisConfig>(&mut buf).expect("cant parse config")
+        let (cfg, dropped) = Self::parse_lenient(&buf).expect("cant parse config");
+        if !dropped.is_empty() {
+            warn!(
+                "ignoring invalid settings {} in {}, run 'artemis-cli config migrate' to fix the file",
+                dropped.join(", "),
+                cfg_path.display()
+            );
+        }
+        cfg
+    }
+
+    /// parses a config file, settings that are missing get their default and settings with an invalid
+    /// value are left out. returns the config and the names of the settings that were left out
+    pub fn parse_lenient(text: &str) -> Result<(Self, Vec<String>)> {
+        let table: toml::Table = toml::from_str(text)?;
+        let mut valid = toml::Table::new();
+        let mut dropped = Vec::new();
+        for (key, value) in table {
+            let single = toml::Table::from_iter([(key.clone(), value.clone())]);
+            if Self::deserialize(single).is_ok() {
+                valid.insert(key, value);
+            } else {
+                dropped.push(key);
+            }
+        }
+        Ok((Self::deserialize(valid)?, dropped))
+    }
+
+    /// rewrites the config file in the current format: fills in the defaults of new settings and drops
+    /// invalid ones. returns the file contents before and after
+    pub fn migrate(path: Option<&Path>) -> Result<(String, String)> {
+        let cfg_path = Self::path(path);
+        let old = fs::read_to_string(&cfg_path).map_err(|e| anyhow!("can't read {}: {}", cfg_path.display(), e))?;
+        let (cfg, dropped) = Self::parse_lenient(&old)?;
+        for key in dropped {
+            warn!("dropping '{}', its value is invalid", key);
+        }
+        let new = toml::to_string(&cfg)?;
+        if new != old {
+            fs::write(&cfg_path, &new).map_err(|e| anyhow!("can't write {}: {}", cfg_path.display(), e))?;
+        }
+        Ok((old, new))
     }
 
     pub fn save(&self, path: Option<&Path>) {
-        let mut home = env::home_dir().expect("cant get HOME directory");
-        home.push(".config/artemis-cli/config.toml");
-        let cfg_path = path.unwrap_or(&home);
+        let cfg_path = &Self::path(path);
 
         if let Some(parent) = cfg_path.parent() {
             fs::create_dir_all(parent).unwrap();
@@ -82,18 +305,239 @@ impl ArtemisConfig {
             .write(true)
             .create(true)
             .truncate(true)
-            .open(path.unwrap_or(&cfg_path))
+            .open(cfg_path)
             .expect("unable to open config file");
 
         let cfg_str = toml::to_string(self).expect("cant Serialize config");
         cfg_file.write_all(cfg_str.as_bytes()).expect("cant write to cfg file");
     }
 
-    pub fn set_base_url(&mut self, base_url: String) {
-        self.base_url = base_url;
+    /// resets the setting `key` to its default, `key` uses the names of the config subcommands
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        let default = Self::default();
+        match key {
+            "base-url" => self.base_url = default.base_url,
+            "user-agent" => self.user_agent = default.user_agent,
+            "auth-path" => self.auth_path = default.auth_path,
+            "editor" => self.editor = default.editor,
+            "verbosity" => self.verbosity = default.verbosity,
+            "remember-me" => self.remember_me = default.remember_me,
+            "credential-store" => self.credential_store = default.credential_store,
+            "auth-mode" => self.auth_mode = default.auth_mode,
+            "clone-protocol" => self.clone_protocol = default.clone_protocol,
+            "clone-dir-template" => self.clone_dir_template = default.clone_dir_template,
+            "commit-author" => self.commit_author = default.commit_author,
+            "timeout" => self.timeout = default.timeout,
+            "task-cache-max-age" => self.task_cache_max_age = default.task_cache_max_age,
+            "time-format" => self.time_format = default.time_format,
+            _ => bail!("unknown config key '{}', valid keys are: {}", key, CONFIG_KEYS.join(", ")),
+        }
+        Ok(())
+    }
+
+    pub fn set_base_url(&mut self, base_url: String) -> Result<()> {
+        self.base_url = normalize_base_url(&base_url)?;
+        Ok(())
+    }
+
+    /// uses `base_url` for this invocation only without changing the saved config
+    pub fn override_base_url(&mut self, base_url: &str) -> Result<()> {
+        self.base_url_override = Some(normalize_base_url(base_url)?);
+        Ok(())
     }
 
     pub fn get_base_url(&self) -> &String {
-        &self.base_url
+        self.base_url_override.as_ref().unwrap_or(&self.base_url)
+    }
+
+    /// sets the user agent, it is sent as header so it may only contain visible ascii characters
+    pub fn set_user_agent(&mut self, user_agent: String) -> Result<()> {
+        if HeaderValue::from_str(&user_agent).is_err() {
+            bail!(
+                "'{}' is not a valid user agent, it may only contain visible ascii characters",
+                user_agent.escape_debug()
+            );
+        }
+        self.user_agent = user_agent;
+        Ok(())
+    }
+
+    pub fn get_user_agent(&self) -> &String {
+        &self.user_agent
+    }
+
+    /// sets the login endpoint, a missing leading slash is added
+    pub fn set_auth_path(&mut self, auth_path: String) {
+        let auth_path = auth_path.trim().trim_end_matches('/');
+        self.auth_path = format!("/{}", auth_path.trim_start_matches('/'));
+    }
+
+    pub fn get_auth_path(&self) -> &String {
+        &self.auth_path
+    }
+
+    pub fn set_editor(&mut self, editor: String) {
+        self.editor = Some(editor);
+    }
+
+    /// the configured editor, or $VISUAL / $EDITOR if none is configured
+    pub fn get_editor(&self) -> Option<String> {
+        self.editor
+            .clone()
+            .or_else(|| env::var("VISUAL").ok())
+            .or_else(|| env::var("EDITOR").ok())
+            .filter(|editor| !editor.trim().is_empty())
+    }
+
+    /// every setting with its current value and an optional note, in the order of `CONFIG_KEYS`
+    pub fn entries(&self) -> Vec<(&'static str, String, Option<&'static str>)> {
+        vec![
+            ("base-url", self.get_base_url().clone(), None),
+            ("user-agent", self.user_agent.clone(), None),
+            ("auth-path", self.auth_path.clone(), None),
+            ("editor", self.get_editor().unwrap_or("(not set)".to_string()), None),
+            ("verbosity", self.verbosity.to_string(), None),
+            ("remember-me", self.remember_me.to_string(), Some(REMEMBER_ME_NOTE)),
+            ("credential-store", self.credential_store.to_string(), None),
+            ("auth-mode", self.auth_mode.to_string(), None),
+            ("clone-protocol", self.clone_protocol.to_string(), None),
+            (
+                "clone-dir-template",
+                self.clone_dir_template.clone().unwrap_or("(not set)".to_string()),
+                None,
+            ),
+            ("commit-author", self.commit_author.clone().unwrap_or("(not set)".to_string()), None),
+            ("timeout", format!("{}s", self.get_timeout()), None),
+            ("task-cache-max-age", format!("{}h", self.task_cache_max_age), None),
+            ("time-format", self.time_format.clone(), None),
+        ]
+    }
+
+    pub fn set_remember_me(&mut self, remember_me: bool) {
+        self.remember_me = remember_me;
+    }
+
+    pub fn get_remember_me(&self) -> bool {
+        self.remember_me
+    }
+
+    pub fn set_credential_store(&mut self, credential_store: CredentialStore) {
+        self.credential_store = credential_store;
+    }
+
+    pub fn get_credential_store(&self) -> CredentialStore {
+        self.credential_store
+    }
+
+    pub fn set_auth_mode(&mut self, auth_mode: AuthMode) {
+        self.auth_mode = auth_mode;
+    }
+
+    pub fn get_auth_mode(&self) -> AuthMode {
+        self.auth_mode
+    }
+
+    pub fn set_clone_protocol(&mut self, clone_protocol: CloneProtocol) {
+        self.clone_protocol = clone_protocol;
+    }
+
+    pub fn get_clone_protocol(&self) -> CloneProtocol {
+        self.clone_protocol
+    }
+
+    /// sets the clone directory template, unknown placeholders are rejected
+    pub fn set_clone_dir_template(&mut self, template: String) -> Result<()> {
+        let mut rest = template.clone();
+        for placeholder in CLONE_DIR_PLACEHOLDERS {
+            rest = rest.replace(placeholder, "");
+        }
+        if rest.contains('{') || rest.contains('}') {
+            bail!("'{}' contains an unknown placeholder, valid ones are: {}", template, CLONE_DIR_PLACEHOLDERS.join(", "));
+        }
+        self.clone_dir_template = Some(template);
+        Ok(())
+    }
+
+    pub fn get_clone_dir_template(&self) -> Option<&String> {
+        self.clone_dir_template.as_ref()
+    }
+
+    /// sets the author of submissions, authors that aren't in the form `Name <email>` are rejected
+    pub fn set_commit_author(&mut self, author: &str) -> Result<()> {
+        self.commit_author = Some(author.parse::<Identity>()?.to_string());
+        Ok(())
+    }
+
+    /// uses `author` for the commits of this invocation only without changing the saved config
+    pub fn override_commit_author(&mut self, author: &str) -> Result<()> {
+        self.commit_author_override = Some(author.parse::<Identity>()?.to_string());
+        Ok(())
+    }
+
+    /// the configured author of submissions, None if the git config should be used
+    pub fn get_commit_author(&self) -> Option<Identity> {
+        let author = self.commit_author_override.as_ref().or(self.commit_author.as_ref())?;
+        author.parse().inspect_err(|e| warn!("{:#}, using the git config", e)).ok()
+    }
+
+    pub fn set_verbosity(&mut self, verbosity: u8) {
+        self.verbosity = verbosity;
+    }
+
+    pub fn get_verbosity(&self) -> u8 {
+        self.verbosity
+    }
+
+    pub fn set_timeout(&mut self, timeout: u64) -> Result<()> {
+        if timeout == 0 {
+            bail!("the timeout has to be at least one second");
+        }
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    /// uses `timeout` for this invocation only without changing the saved config
+    pub fn override_timeout(&mut self, timeout: u64) -> Result<()> {
+        if timeout == 0 {
+            bail!("the timeout has to be at least one second");
+        }
+        self.timeout_override = Some(timeout);
+        Ok(())
+    }
+
+    /// logs in again for this invocation instead of restoring the stored login token
+    pub fn force_fresh_login(&mut self) {
+        self.fresh_login = true;
+    }
+
+    pub fn get_fresh_login(&self) -> bool {
+        self.fresh_login
+    }
+
+    pub fn set_task_cache_max_age(&mut self, hours: u64) {
+        self.task_cache_max_age = hours;
+    }
+
+    pub fn get_task_cache_max_age(&self) -> TimeDelta {
+        TimeDelta::hours(self.task_cache_max_age as i64)
+    }
+
+    /// sets the time format, patterns chrono can't render are rejected
+    pub fn set_time_format(&mut self, format: &str) -> Result<()> {
+        self.time_format = format.parse::<TimeFormat>()?.to_string();
+        Ok(())
+    }
+
+    /// the configured time format, 24h if the config holds one that can't be parsed
+    pub fn get_time_format(&self) -> TimeFormat {
+        self.time_format.parse().unwrap_or_else(|e| {
+            warn!("{:#}, using 24h", e);
+            TimeFormat::default()
+        })
+    }
+
+    /// the timeout in seconds
+    pub fn get_timeout(&self) -> u64 {
+        self.timeout_override.unwrap_or(self.timeout)
     }
 }
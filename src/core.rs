@@ -1,2 +1,4 @@
 pub mod adapter;
 pub mod git;
+pub mod metadata;
+pub mod stomp;
@@ -15,67 +15,629 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 use std::{
+    cmp::Reverse,
     fmt::{Display, write},
+    fs,
+    io::{self, IsTerminal},
+    path::Path,
     sync::Arc,
     time::Duration,
 };
 
-use anyhow::{Result, anyhow};
-use chrono::{DateTime, FixedOffset};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use clap::ValueEnum;
 use colored::Colorize;
-use keyring::Entry;
-use log::{debug, error, info, trace};
+use futures_util::{SinkExt, StreamExt};
+use log::{LevelFilter, debug, error, info, trace, warn};
 use reqwest::{
-    Client, Response,
+    Client, RequestBuilder, Response, StatusCode,
     cookie::{CookieStore, Jar},
+    multipart::{Form, Part},
 };
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use tokio_tungstenite::tungstenite::{Message, client::IntoClientRequest};
+
+use super::{
+    git::HttpAuth,
+    metadata::{CachedResult, ResultCache, TaskIndex},
+    stomp::Frame,
+};
+use crate::{
+    config::ArtemisConfig,
+    credentials::Credentials,
+    prompt::read_secret,
+    time_format::format_time_of_day,
+};
+
+/// key of the cookies of the current session in the credential store
+pub const SESSION_KEY: &str = "jwt-token";
+/// key of the stored jwt when it is sent as bearer token, kept apart from the cookie session
+const BEARER_KEY: &str = "bearer-token";
+
+/// wait before retrying a rate limited request if artemis doesn't say how long
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+/// never wait longer than this for a rate limit, even if artemis asks for it
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+
+/// the STOMP destination artemis sends the new results of the logged in student to
+const RESULT_TOPIC: &str = "/user/topic/newResults";
+
+/// the largest file artemis accepts for file upload exercises
+const MAX_UPLOAD_SIZE: u64 = 20 * 1024 * 1024;
+
+/// lowercase build log fragments of compilers and build tools that mean the submission itself doesn't build.
+/// a plain `error:` isn't enough, the build runner reports its own failures like that
+const COMPILE_ERROR_MARKERS: &[&str] = &["compilation error", "cannot find symbol", "error[e", "syntaxerror"];
+/// lowercase build log fragments that point to a problem of the build agent
+const INFRASTRUCTURE_MARKERS: &[&str] = &[
+    "timed out",
+    "timeout",
+    "execution took longer than",
+    "no space left on device",
+    "could not resolve",
+    "connection refused",
+    "connection reset",
+    "no route to host",
+    "cannot connect to the docker daemon",
+];
+
+/// reads the string `field` of `value` without the JSON quotes and escapes `Value::to_string` would keep
+fn json_str(value: &Value, field: &str) -> Result<String> {
+    match value.get(field) {
+        Some(Value::String(text)) => Ok(text.clone()),
+        Some(other) => Err(anyhow!("expected '{}' to be a string but got {}", field, other)),
+        None => Err(anyhow!("the response from artemis has no '{}' field", field)),
+    }
+}
+
+/// the long-lived jwt cookie artemis hands out for `rememberMe` logins, stored separately from the
+/// session so re-authentication works without the password
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RememberMeToken {
+    cookie: String,
+    expires: DateTime<Utc>,
+}
+
+impl RememberMeToken {
+    const KEY: &str = "remember-me";
+
+    /// extracts the jwt cookie from a login response, None if artemis didn't make it persistent
+    fn from_response(response: &Response) -> Option<Self> {
+        let cookie = response.cookies().find(|cookie| cookie.name() == "jwt")?;
+        let expires = match (cookie.max_age(), cookie.expires()) {
+            (Some(max_age), _) => Utc::now() + max_age,
+            (None, Some(expires)) => DateTime::<Utc>::from(expires),
+            (None, None) => return None,
+        };
+        Some(Self {
+            cookie: format!("jwt={}", cookie.value()),
+            expires,
+        })
+    }
+
+    fn load(credentials: &Credentials) -> Option<Self> {
+        let stored = credentials.get(Self::KEY).ok()??;
+        serde_json::from_str(&stored).ok()
+    }
+
+    fn save(&self, credentials: &Credentials) -> Result<()> {
+        credentials.set(Self::KEY, &serde_json::to_string(self)?)
+    }
+
+    fn delete(credentials: &Credentials) {
+        if let Err(e) = credentials.delete(Self::KEY) {
+            debug!("can't delete the remember-me token: {}", e);
+        }
+    }
+}
+
+/// returned when a login is needed but the username or password isn't configured
+#[derive(Debug)]
+pub struct MissingCredentials {
+    missing: Vec<&'static str>,
+}
+
+impl Display for MissingCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "you haven't configured your {} yet, run:", self.missing.join(" and "))?;
+        for key in &self.missing {
+            write!(f, "\n    artemis-cli config {0} [{1}]", key, key.to_uppercase())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MissingCredentials {}
+
+/// returned when artemis rejects the login, e.g. with 401 for a wrong password
+#[derive(Debug)]
+pub struct LoginRejected {
+    status: StatusCode,
+    message: String,
+}
+
+impl LoginRejected {
+    /// whether artemis rejected the credentials with 401
+    pub fn unauthorized(&self) -> bool {
+        self.status == StatusCode::UNAUTHORIZED
+    }
+}
+
+impl Display for LoginRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for LoginRejected {}
+
+/// returned when a submission has no finished result yet, e.g. because its build is still running
+#[derive(Debug)]
+pub struct BuildStillRunning;
+
+impl Display for BuildStillRunning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "build still running — check later with fetch")
+    }
+}
+
+impl std::error::Error for BuildStillRunning {}
 
 pub struct Adapter {
     client: Client,
     cookies: Arc<Jar>,
     base_url: String,
+    auth_path: String,
+    /// if false the session only lives in memory and nothing is written to the keyring
+    remember_me: bool,
+    credentials: Credentials,
+    auth_mode: AuthMode,
+    clone_protocol: CloneProtocol,
+    /// the jwt sent with every request in bearer mode, None in cookie mode
+    bearer_token: Option<String>,
+    /// read result details from the `ResultCache` instead of downloading them again
+    use_result_cache: bool,
+    /// --fresh-login: neither the stored session nor the remember-me token are used, the password is asked for
+    fresh_login: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, JsonSchema)]
 pub struct Task {
     pub(crate) title: String,
     pub(crate) id: u64,
     pub(crate) is_active: bool,
     pub(crate) completed: bool,
+    pub(crate) due_date: Option<DateTime<FixedOffset>>,
+    pub(crate) release_date: Option<DateTime<FixedOffset>>,
+    pub(crate) categories: Vec<String>,
+    pub(crate) submission_policy: Option<SubmissionPolicy>,
+    pub(crate) difficulty: Option<Difficulty>,
+    pub(crate) max_points: Option<f64>,
+    /// the graded participation, None if the task hasn't been started
+    pub(crate) participation_id: Option<u64>,
+    /// the number of results of the graded participation, grows when a submission was built
+    pub(crate) result_count: usize,
+    pub(crate) latest_score: Option<f64>,
+    /// the ids of the exercises that have to be completed before this one can be started
+    pub(crate) prerequisites: Vec<u64>,
+    /// students work on the exercise in teams that share a participation and repository
+    pub(crate) team_mode: bool,
 }
 
-#[derive(Clone, Debug)]
+/// the difficulty instructors assigned to an exercise, ordered from easy to hard
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, JsonSchema)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    fn parse(difficulty: &str) -> Option<Self> {
+        match difficulty {
+            "EASY" => Some(Difficulty::Easy),
+            "MEDIUM" => Some(Difficulty::Medium),
+            "HARD" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    /// the relative effort used to rank exercises by points per effort
+    pub fn effort(&self) -> f64 {
+        match self {
+            Difficulty::Easy => 1.0,
+            Difficulty::Medium => 2.0,
+            Difficulty::Hard => 3.0,
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Medium => write!(f, "medium"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+/// limits on the number of submissions of a programming exercise
+#[derive(Clone, Copy, Debug, Serialize, JsonSchema)]
+pub enum SubmissionPolicy {
+    /// every submission after `limit` costs `penalty` percent
+    Penalty { limit: u64, penalty: f64 },
+    /// the repository is locked after `limit` submissions
+    LockRepository { limit: u64 },
+}
+
+impl SubmissionPolicy {
+    /// the number of submissions the policy allows without consequences
+    pub fn limit(&self) -> u64 {
+        match self {
+            SubmissionPolicy::Penalty { limit, .. } | SubmissionPolicy::LockRepository { limit } => *limit,
+        }
+    }
+}
+
+impl Display for SubmissionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmissionPolicy::Penalty { limit, penalty } => {
+                write!(f, "each submission after attempt {} costs {}%", limit, penalty)
+            }
+            SubmissionPolicy::LockRepository { limit } => {
+                write!(f, "the repository is locked after {} submissions", limit)
+            }
+        }
+    }
+}
+
+impl Task {
+    /// whether the due date has passed without the task being completed
+    pub fn is_overdue(&self) -> bool {
+        !self.completed && self.due_date.is_some_and(|due| due < Local::now())
+    }
+
+    /// the submissions left before the submission policy applies, None if the task has no policy.
+    /// every built submission gets a result, so the results are counted as used attempts
+    pub fn remaining_attempts(&self) -> Option<u64> {
+        let limit = self.submission_policy?.limit();
+        Some(limit.saturating_sub(self.result_count as u64))
+    }
+
+    /// max points per effort, None if the difficulty or the points are unknown
+    pub fn value(&self) -> Option<f64> {
+        Some(self.max_points? / self.difficulty?.effort())
+    }
+
+    /// tasks without a release date are always released
+    pub fn is_released(&self) -> bool {
+        self.release_date.is_none_or(|release| release <= Local::now())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
 pub struct Course {
     pub(crate) id: u64,
     pub(crate) title: String,
+    pub(crate) short_name: Option<String>,
+    /// e.g. `WS24`, tells apart courses that have the same title in every term
+    pub(crate) semester: Option<String>,
     pub(crate) tasks: Vec<Task>,
 }
 
-#[derive(Clone, Debug)]
+impl Course {
+    /// the incomplete task with the nearest due date that hasn't passed yet
+    pub fn next_task(&self) -> Option<&Task> {
+        let now = Local::now();
+        self.tasks
+            .iter()
+            .filter(|task| task.is_released() && !task.completed && task.due_date.is_some_and(|due| due > now))
+            .filter(|task| self.locked_by(task).is_empty())
+            .min_by_key(|task| task.due_date)
+    }
+
+    /// the prerequisites of `task` that aren't completed yet, prerequisites outside of this course are ignored
+    pub fn locked_by(&self, task: &Task) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|other| task.prerequisites.contains(&other.id) && !other.completed)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
 pub struct Test {
     pub(crate) name: String,
     pub(crate) passed: bool,
     pub(crate) explanation: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// an issue found by the static code analysis, these can cost points without failing a test
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StaticAnalysisIssue {
+    pub(crate) file: String,
+    pub(crate) line: Option<u64>,
+    pub(crate) rule: String,
+    pub(crate) message: String,
+}
+
+/// textual feedback that isn't the assertion of a test, e.g. written by a tutor or generated
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TextFeedback {
+    /// the `type` of the feedback as reported by artemis, e.g. MANUAL or AUTOMATIC
+    pub(crate) kind: String,
+    pub(crate) title: Option<String>,
+    pub(crate) text: String,
+}
+
+/// a point deduction or bonus of the manual assessment, given for a grading criterion or a line of code
+#[derive(Clone, Debug, Serialize)]
+pub struct AssessmentFeedback {
+    pub(crate) criterion: String,
+    pub(crate) points: Option<f64>,
+    pub(crate) comment: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LogStatement {
     pub(crate) time: DateTime<FixedOffset>,
     pub(crate) log: String,
 }
 
+/// how requests to artemis are authenticated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthMode {
+    /// the session cookie artemis sets on login
+    #[default]
+    Cookie,
+    /// the jwt of the login in an `Authorization: Bearer` header, for proxies that strip cookies.
+    /// git only sends it to remotes on the host of artemis, see `HttpAuth`
+    Bearer,
+}
+
+impl AuthMode {
+    /// the credential key the session of this mode is stored under
+    pub fn session_key(self) -> &'static str {
+        match self {
+            AuthMode::Cookie => SESSION_KEY,
+            AuthMode::Bearer => BEARER_KEY,
+        }
+    }
+}
+
+impl Display for AuthMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthMode::Cookie => write!(f, "cookie"),
+            AuthMode::Bearer => write!(f, "bearer"),
+        }
+    }
+}
+
+/// how repositories that artemis serves over https are cloned, ssh uris are always cloned over ssh
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CloneProtocol {
+    /// over https, authenticated by the session, works without ssh keys
+    #[default]
+    Https,
+    /// over ssh with the keys of the ssh-agent, for instances that also serve the repositories over ssh
+    Ssh,
+}
+
+impl Display for CloneProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloneProtocol::Https => write!(f, "https"),
+            CloneProtocol::Ssh => write!(f, "ssh"),
+        }
+    }
+}
+
+/// artemis allows a graded participation and a practice participation (test run) per exercise
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ParticipationType {
+    #[default]
+    Graded,
+    Practice,
+}
+
+impl Display for ParticipationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParticipationType::Graded => write!(f, "graded"),
+            ParticipationType::Practice => write!(f, "practice"),
+        }
+    }
+}
+
+/// a hint of an exercise, the content is only shown once artemis made the hint available
+#[derive(Clone, Debug, Serialize)]
+pub struct ExerciseHint {
+    pub(crate) id: u64,
+    pub(crate) title: String,
+    pub(crate) content: Option<String>,
+    pub(crate) available: bool,
+}
+
+/// a post in the announcements of a course
+#[derive(Clone, Debug, Serialize)]
+pub struct Announcement {
+    pub(crate) title: Option<String>,
+    pub(crate) author: Option<String>,
+    pub(crate) date: Option<DateTime<FixedOffset>>,
+    pub(crate) content: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Competency {
+    pub(crate) id: u64,
+    pub(crate) title: String,
+    /// the progress of the student in percent
+    pub(crate) progress: f64,
+    /// the progress in percent needed to master the competency
+    pub(crate) mastery_threshold: Option<f64>,
+}
+
+/// the artemis account of the logged in user
+#[derive(Clone, Debug, Serialize)]
+pub struct Account {
+    pub(crate) login: String,
+    pub(crate) name: String,
+    pub(crate) email: String,
+}
+
+/// the team a student works in on a team exercise, all members share one repository
+#[derive(Clone, Debug, Serialize)]
+pub struct Team {
+    pub(crate) name: String,
+    pub(crate) short_name: String,
+    pub(crate) members: Vec<Account>,
+    /// the shared repository, None until the team started the exercise
+    pub(crate) repository: Option<String>,
+}
+
+/// a single result of a participation as listed in the exercise details
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResultInfo {
+    pub(crate) id: u64,
+    pub(crate) completion_date: DateTime<FixedOffset>,
+    pub(crate) build_failed: bool,
+    /// the score in percent as computed by artemis
+    pub(crate) score: Option<f64>,
+    /// the commit the result was built from
+    pub(crate) commit_hash: Option<String>,
+}
+
+/// one result in the history of a participation
+#[derive(Clone, Debug, Serialize)]
+pub struct HistoryEntry {
+    pub(crate) date: DateTime<FixedOffset>,
+    pub(crate) score: Option<f64>,
+    pub(crate) passed: Option<u64>,
+    pub(crate) total: Option<u64>,
+}
+
+/// the feedbacks of a result, sorted by their kind
+struct ResultDetails {
+    tests: Vec<Test>,
+    static_analysis: Vec<StaticAnalysisIssue>,
+    feedback: Vec<TextFeedback>,
+    assessment: Vec<AssessmentFeedback>,
+}
+
+/// the released example solution of an exercise
+#[derive(Clone, Debug)]
+pub enum ExampleSolution {
+    /// url of the solution repository of a programming exercise, ssh or https like the student repository
+    Repository(String),
+    /// the solution of a text or modeling exercise
+    Text(String),
+}
+
+/// the build state of a participation as seen in the exercise details
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildState {
+    pub(crate) participation_id: u64,
+    /// the newest result, a new result id means a build has completed
+    pub(crate) latest_result: Option<u64>,
+    /// whether a submission is still waiting for its result
+    pub(crate) pending: bool,
+}
+
+/// the latest result of a task
+#[derive(Clone, Debug)]
+pub struct TaskResult {
+    pub(crate) completion_date: DateTime<FixedOffset>,
+    pub(crate) commit_hash: Option<String>,
+    pub(crate) score: Option<f64>,
+    pub(crate) outcome: TestOutcome,
+    pub(crate) static_analysis: Vec<StaticAnalysisIssue>,
+    pub(crate) feedback: Vec<TextFeedback>,
+    pub(crate) assessment: Vec<AssessmentFeedback>,
+}
+
+impl TaskResult {
+    pub fn failed(&self) -> bool {
+        self.outcome.failed()
+    }
+
+    /// whether this result belongs to `commit`, results without a commit hash are compared by their
+    /// completion date instead
+    pub fn is_for_commit(&self, commit: &str, pushed_at: DateTime<Local>) -> bool {
+        match &self.commit_hash {
+            Some(hash) => hash == commit,
+            None => self.completion_date > pushed_at,
+        }
+    }
+}
+
+/// either the test results or the build logs if the build failed
+#[derive(Clone, Debug)]
+pub enum TestOutcome {
+    TestResults(Vec<Test>),
+    BuildFailure(Vec<LogStatement>),
+}
+
+impl TestOutcome {
+    /// true if the build failed or at least one test didn't pass
+    pub fn failed(&self) -> bool {
+        match self {
+            TestOutcome::TestResults(tests) => tests.iter().any(|test| !test.passed),
+            TestOutcome::BuildFailure(_) => true,
+        }
+    }
+
+    /// guesses whether a build failed because of the build agent rather than the submission: the log is
+    /// empty or mentions e.g. a timeout or an unreachable host, and no line looks like a compiler error
+    pub fn is_infrastructure_failure(&self) -> bool {
+        let TestOutcome::BuildFailure(logs) = self else {
+            return false;
+        };
+        let lines = || logs.iter().map(|statement| statement.log.to_lowercase());
+        if lines().any(|line| Self::is_compile_error(&line)) {
+            return false;
+        }
+        logs.is_empty() || lines().any(|line| INFRASTRUCTURE_MARKERS.iter().any(|marker| line.contains(marker)))
+    }
+
+    /// whether a lowercase log line is a compiler error, e.g. `Main.java:12: error: ...` or `main.c:3:5: error: ...`
+    fn is_compile_error(line: &str) -> bool {
+        COMPILE_ERROR_MARKERS.iter().any(|marker| line.contains(marker))
+            || line
+                .split_once(": error:")
+                .is_some_and(|(location, _)| location.ends_with(|c: char| c.is_ascii_digit()))
+    }
+}
+
+impl LogStatement {
+    /// the time of day in the configured time format, or the full RFC 3339 timestamp when debug logging is enabled
+    pub fn timestamp(&self) -> String {
+        if log::max_level() >= LevelFilter::Debug {
+            self.time.to_rfc3339()
+        } else {
+            format_time_of_day(&self.time)
+        }
+    }
+}
+
 impl Display for LogStatement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write(
             f,
             format_args!(
-                "{:<30} {}",
-                self.time,
-                if self.log[0..7] == *"[ERROR]" {
+                "{:<8} {}",
+                self.timestamp(),
+                if self.log.starts_with("[ERROR]") {
                     self.log.red()
-                } else if self.log[0..6] == *"[INFO]" {
+                } else if self.log.starts_with("[INFO]") {
                     self.log.bright_blue()
                 } else {
                     self.log.normal()
@@ -86,11 +648,20 @@ impl Display for LogStatement {
 }
 
 impl Adapter {
-    pub async fn init(timeout: u8, base_url: &str) -> Self {
+    pub async fn init(timeout: u64, cfg: &ArtemisConfig) -> Result<Self> {
+        let base_url = cfg.get_base_url().as_str();
+
+        debug!("using user agent: {}", cfg.get_user_agent());
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
             reqwest::header::USER_AGENT,
-            "Mozilla/5.0 (compatible; RustScraper/1.0)".parse().unwrap(),
+            cfg.get_user_agent().parse().with_context(|| {
+                format!(
+                    "the configured user agent '{}' is not a valid header value, reset it with \
+                    'artemis-cli config unset user-agent'",
+                    cfg.get_user_agent().escape_debug()
+                )
+            })?,
         );
         headers.insert(
             reqwest::header::ACCEPT,
@@ -101,46 +672,116 @@ impl Adapter {
 
         // jar holds onto our cookies
         let jar = Arc::new(Jar::default());
-        let entry = Entry::new("artemiscli", "jwt-token").expect("cant create keyring entry for jwt token");
-
-        let mut restored_cookie = false;
-        if let Ok(cookie) = entry.get_password() {
-            jar.add_cookie_str(&cookie, &reqwest::Url::parse(base_url).unwrap());
-            restored_cookie = true;
+        let credentials = Credentials::new(cfg);
+
+        let auth_mode = cfg.get_auth_mode();
+        let mut bearer_token = None;
+        let mut restored_session = false;
+        if cfg.get_fresh_login() {
+            debug!("--fresh-login given, not restoring the stored login token");
+        } else if cfg.get_remember_me()
+            && let Some(session) = credentials.get(auth_mode.session_key())?
+        {
+            match auth_mode {
+                AuthMode::Cookie => jar.add_cookie_str(&session, &reqwest::Url::parse(base_url).unwrap()),
+                AuthMode::Bearer => bearer_token = Some(session),
+            }
+            restored_session = true;
         }
 
-        let client = Client::builder()
-            .default_headers(headers)
-            .timeout(Duration::from_secs(timeout as u64))
-            .cookie_store(true)
-            .cookie_provider(jar.clone())
-            .build()
-            .expect("cant build the reqwest client");
+        let mut builder = Client::builder().default_headers(headers).timeout(Duration::from_secs(timeout));
+        // in bearer mode cookies are neither stored nor sent, the jwt goes into the authorization header instead
+        if auth_mode == AuthMode::Cookie {
+            builder = builder.cookie_store(true).cookie_provider(jar.clone());
+        }
+        let client = builder.build()?;
 
         let mut s = Self {
             client,
             cookies: jar,
             base_url: base_url.to_owned(),
+            auth_path: cfg.get_auth_path().to_owned(),
+            remember_me: cfg.get_remember_me(),
+            credentials,
+            auth_mode,
+            clone_protocol: cfg.get_clone_protocol(),
+            bearer_token,
+            use_result_cache: true,
+            fresh_login: cfg.get_fresh_login(),
         };
 
-        // if we weren't able to restore our old cookie, we create a new one by logging in again
-        if !restored_cookie {
-            s.login().await.expect("Login failed");
+        // if we weren't able to restore our old session, we create a new one by logging in again
+        if !restored_session {
+            // without a remember-me token the login needs the password, so fail before sending anything
+            let has_token = s.uses_remember_me_token() && RememberMeToken::load(&s.credentials).is_some();
+            if !s.fresh_login && !has_token {
+                s.username_and_password()?;
+            }
+            s.login().await?;
         }
-        s
+        Ok(s)
+    }
+
+    /// wraps errors of requests that didn't reach the server at all
+    fn connection_error(&self, e: reqwest::Error) -> anyhow::Error {
+        anyhow!("could not reach {} — check your connection and base-url config ({})", self.base_url, e)
     }
 
+    /// remember-me tokens are cookies, so they are only used in cookie mode
+    fn uses_remember_me_token(&self) -> bool {
+        self.remember_me && self.auth_mode == AuthMode::Cookie
+    }
+
+    /// adds the bearer token to `request` in bearer mode, in cookie mode the client sends the cookies by itself
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.bearer_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// sends `request` and, if artemis answers with 429, waits as long as its `Retry-After` asks and retries once
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let request = self.authorized(request);
+        let retry = request.try_clone();
+        let response = request.send().await.map_err(|e| self.connection_error(e))?;
+        let (StatusCode::TOO_MANY_REQUESTS, Some(retry)) = (response.status(), retry) else {
+            return Ok(response);
+        };
+
+        let wait = Self::retry_after(&response).min(MAX_RETRY_AFTER);
+        warn!("artemis is rate limiting requests, retrying in {}s", wait.as_secs());
+        tokio::time::sleep(wait).await;
+        retry.send().await.map_err(|e| self.connection_error(e))
+    }
+
+    /// the delay requested by the `Retry-After` header, given either in seconds or as a date
+    fn retry_after(response: &Response) -> Duration {
+        let Some(value) = response.headers().get(reqwest::header::RETRY_AFTER).and_then(|value| value.to_str().ok())
+        else {
+            return DEFAULT_RETRY_AFTER;
+        };
+        if let Ok(seconds) = value.trim().parse() {
+            return Duration::from_secs(seconds);
+        }
+        DateTime::parse_from_rfc2822(value)
+            .ok()
+            .and_then(|date| (date.with_timezone(&Utc) - Utc::now()).to_std().ok())
+            .unwrap_or(DEFAULT_RETRY_AFTER)
+    }
+
+    /// fetches `uri`, logging in again and retrying once if the session expired
     async fn fetch_json(&mut self, uri: &str) -> Result<Response> {
-        let response = self
-            .client
-            .get(uri)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .expect(&format!("can't send get request to: {}, do you have authorization?", uri));
+        let mut response = self.send(self.client.get(uri).header("Accept", "application/json")).await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
-            self.login().await.expect("login failed: ");
+            info!("the session expired, logging in again");
+            // drop the stale session first, login stores the fresh one but a failed login would leave it behind
+            if let Err(e) = self.credentials.delete(self.auth_mode.session_key()) {
+                debug!("can't delete the expired session: {}", e);
+            }
+            self.login().await?;
+            response = self.send(self.client.get(uri).header("Accept", "application/json")).await?;
         }
         if !response.status().is_success() {
             error!("coudn't fetch json from {}: {}", uri, response.status());
@@ -149,45 +790,170 @@ impl Adapter {
         Ok(response)
     }
 
+    /// logs in to artemis, preferring a still valid remember-me token over the stored password
     pub async fn login(&mut self) -> Result<()> {
-        let uname = Entry::new("artemiscli", "username").expect("cant create keyring entry for username");
-        let pwd = Entry::new("artemiscli", "password").expect("cant create keyring entry for password");
+        // a stale bearer token must not be sent along with the login
+        self.bearer_token = None;
+        if !self.fresh_login && self.uses_remember_me_token() && self.login_with_remember_me().await {
+            info!("re-authenticated using the remember-me token");
+            return self.save_session();
+        }
+
+        let (username, password) = self.login_credentials()?;
 
         let auth = json!({
-            "username": uname.get_password().expect("you havent configured a username yet, use 'artemis-cli config username [USERNAME]' and try again"),
-            "password": pwd.get_password().expect("you havent configured a password yet, use 'artemis-cli config password [PASSWORD]' and try again"),
-            "rememberMe": true,
+            "username": username,
+            "password": password,
+            "rememberMe": self.remember_me,
         });
 
-        let response = self
-            .client
-            .post("https://artemis-app.inf.tu-dresden.de/api/public/authenticate")
-            .json(&auth)
-            .send()
-            .await
-            .expect("can't send authentication request");
+        let response = self.send(self.client.post(format!("{}{}", self.base_url, self.auth_path)).json(&auth)).await?;
 
         if response.status().is_success() {
             info!("succesfully logged in");
 
-            let entry = Entry::new("artemiscli", "jwt-token")?;
-            // save the cookie for later use
-            entry
-                .set_password(
-                    self.cookies
-                        .cookies(&reqwest::Url::parse(self.base_url.as_str()).unwrap())
-                        .expect("no cookies found for artemis")
-                        .to_str()
-                        .expect("cookies are invalid utf8"),
-                )
-                .expect("can't access keyring");
-            Ok(())
+            if self.auth_mode == AuthMode::Bearer {
+                self.bearer_token = Some(Self::bearer_token_from_login(response).await?);
+                return if self.remember_me { self.save_session() } else { Ok(()) };
+            }
+            if !self.remember_me {
+                return Ok(());
+            }
+            match RememberMeToken::from_response(&response) {
+                Some(token) => token.save(&self.credentials)?,
+                None => debug!("artemis didn't send a persistent jwt cookie, no remember-me token stored"),
+            }
+            self.save_session()
         } else {
-            error!("cant log in to artemis {:?}", response.status());
-            Err(anyhow!("login failed, aborting..."))
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+
+            let message = match Self::parse_error_reason(&body) {
+                Some(reason) if reason.to_lowercase().contains("not activated") => {
+                    error!("cant log in to artemis {:?}: {}", status, reason);
+                    "login failed: your account is not activated yet, check your email for the activation link"
+                        .to_string()
+                }
+                Some(reason) => {
+                    error!("cant log in to artemis {:?}: {}", status, reason);
+                    format!("login failed: {}", reason)
+                }
+                None => {
+                    error!("cant log in to artemis {:?}", status);
+                    "login failed, aborting...".to_string()
+                }
+            };
+            Err(LoginRejected { status, message }.into())
+        }
+    }
+
+    /// the jwt of a successful login, newer artemis versions set it as cookie, older ones return it as `id_token`
+    async fn bearer_token_from_login(response: Response) -> Result<String> {
+        let cookie = response.cookies().find(|cookie| cookie.name() == "jwt").map(|cookie| cookie.value().to_string());
+        let body = response.text().await.unwrap_or_default();
+        cookie
+            .or_else(|| {
+                let json: Value = serde_json::from_str(&body).ok()?;
+                json.get("id_token").and_then(Value::as_str).map(str::to_string)
+            })
+            .ok_or(anyhow!("artemis didn't hand out a jwt, switch back with 'artemis-cli config auth-mode cookie'"))
+    }
+
+    /// the username and password to log in with. with --fresh-login the password is asked for on a terminal, so a
+    /// login works even if the stored password is outdated
+    fn login_credentials(&self) -> Result<(String, String)> {
+        if !self.fresh_login || !io::stdin().is_terminal() {
+            return self.username_and_password();
+        }
+        let username = self.credentials.get("username")?.ok_or(MissingCredentials { missing: vec!["username"] })?;
+        let password = read_secret(&format!("password for {}: ", username))?;
+        Ok((username, password))
+    }
+
+    /// the configured username and password, a `MissingCredentials` error if either isn't configured
+    fn username_and_password(&self) -> Result<(String, String)> {
+        let username = self.credentials.get("username")?;
+        let password = self.credentials.get("password")?;
+        match (username, password) {
+            (Some(username), Some(password)) => Ok((username, password)),
+            (username, password) => {
+                let missing = [("username", username.is_none()), ("password", password.is_none())]
+                    .into_iter()
+                    .filter_map(|(key, missing)| missing.then_some(key))
+                    .collect();
+                Err(MissingCredentials { missing }.into())
+            }
         }
     }
 
+    /// removes the stored session and remember-me token
+    pub fn forget_session(cfg: &ArtemisConfig) -> Result<()> {
+        let credentials = Credentials::new(cfg);
+        credentials.delete(SESSION_KEY)?;
+        credentials.delete(BEARER_KEY)?;
+        RememberMeToken::delete(&credentials);
+        Ok(())
+    }
+
+    /// saves the cookies or the bearer token of the current session for later use
+    fn save_session(&self) -> Result<()> {
+        if let Some(token) = &self.bearer_token {
+            return self.credentials.set(BEARER_KEY, token);
+        }
+        self.credentials.set(
+            SESSION_KEY,
+            self.cookies
+                .cookies(&reqwest::Url::parse(self.base_url.as_str()).unwrap())
+                .expect("no cookies found for artemis")
+                .to_str()
+                .expect("cookies are invalid utf8"),
+        )
+    }
+
+    /// restores the stored remember-me token and checks that artemis still accepts it.
+    /// expired or rejected tokens are removed so the next login falls back to the password.
+    async fn login_with_remember_me(&mut self) -> bool {
+        let Some(token) = RememberMeToken::load(&self.credentials) else {
+            return false;
+        };
+        if token.expires <= Utc::now() {
+            debug!("the remember-me token expired at {}", token.expires);
+            RememberMeToken::delete(&self.credentials);
+            return false;
+        }
+
+        self.cookies
+            .add_cookie_str(&token.cookie, &reqwest::Url::parse(self.base_url.as_str()).unwrap());
+        let response = self
+            .client
+            .get(format!("{}/api/public/account", self.base_url))
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status().is_success() => true,
+            Ok(response) => {
+                debug!("artemis rejected the remember-me token: {}", response.status());
+                RememberMeToken::delete(&self.credentials);
+                false
+            }
+            Err(e) => {
+                debug!("can't verify the remember-me token: {}", e);
+                false
+            }
+        }
+    }
+
+    pub async fn get_account(&mut self) -> Result<Account> {
+        let text = self
+            .fetch_json(format!("{}/api/public/account", self.base_url).as_str())
+            .await?
+            .text()
+            .await?;
+
+        Self::parse_account(&text)
+    }
+
     pub async fn get_all_courses(&mut self) -> Result<Vec<Course>> {
         debug!("fetching course names...");
 
@@ -197,7 +963,17 @@ impl Adapter {
             .text()
             .await?;
 
-        let mut deserializer = serde_json::Deserializer::from_str(&text);
+        let courses = Self::parse_courses(&text)?;
+        if let Err(e) = TaskIndex::update(&courses) {
+            warn!("can't update the task index: {:#}", e);
+        }
+        Ok(courses)
+    }
+
+    /// parses the courses of the dashboard. newer artemis versions wrap every course in an object
+    /// with a `course` field, older ones return the course fields at the top level
+    pub(crate) fn parse_courses(text: &str) -> Result<Vec<Course>> {
+        let mut deserializer = serde_json::Deserializer::from_str(text);
         let json = Value::deserialize(&mut deserializer)?;
 
         trace!("start deserializing courses page...");
@@ -207,14 +983,167 @@ impl Adapter {
         let mut course_list = Vec::new();
 
         for course_info in raw_course_array {
-            let course = course_info.get("course").unwrap();
-            course_list.push(Self::parse_course(course).unwrap());
+            let course = course_info.get("course").filter(|course| course.is_object()).unwrap_or(course_info);
+            course_list.push(Self::parse_course(course)?);
         }
 
         Ok(course_list)
     }
 
-    pub async fn get_latest_test_result(&mut self, taskid: u64) -> Result<Vec<Test>> {
+    pub async fn get_competencies(&mut self, courseid: u64) -> Result<Vec<Competency>> {
+        let text = self
+            .fetch_json(format!("{}/api/courses/{}/competencies", self.base_url, courseid).as_str())
+            .await?
+            .text()
+            .await?;
+
+        Self::parse_competencies(&text)
+    }
+
+    /// the latest `count` announcements of a course, newest first
+    pub async fn get_announcements(&mut self, courseid: u64, count: usize) -> Result<Vec<Announcement>> {
+        let posts_uri = format!(
+            "{}/api/courses/{}/posts?courseWideContexts=ANNOUNCEMENT&postSortCriterion=CREATION_DATE\
+             &sortingOrder=DESCENDING&pagingEnabled=true&page=0&size={}",
+            self.base_url, courseid, count
+        );
+        let text = self.fetch_json(&posts_uri).await?.text().await?;
+        let mut announcements = Self::parse_announcements(&text)?;
+        announcements.truncate(count);
+        Ok(announcements)
+    }
+
+    /// all hints of an exercise, marking the ones that are available to the student right now
+    pub async fn get_exercise_hints(&mut self, taskid: u64) -> Result<Vec<ExerciseHint>> {
+        let hints_uri = format!("{}/api/exercises/{}/exercise-hints", self.base_url, taskid);
+        let text = self.fetch_json(&hints_uri).await?.text().await?;
+
+        // older artemis versions have no endpoint for the available hints and return only those
+        let available_uri = format!("{}/api/exercises/{}/exercise-hints/available", self.base_url, taskid);
+        let available = match self.fetch_json(&available_uri).await {
+            Ok(response) => Some(Self::parse_hint_ids(&response.text().await?)?),
+            Err(e) => {
+                debug!("can't fetch the available hints, treating all as available: {}", e);
+                None
+            }
+        };
+
+        Self::parse_exercise_hints(&text, available.as_deref())
+    }
+
+    /// the team of the logged in student for a team exercise
+    pub async fn get_team(&mut self, taskid: u64) -> Result<Team> {
+        let details_uri = format!("{}/api/exercises/{}/details", self.base_url, taskid);
+        let text = self.fetch_json(&details_uri).await?.text().await?;
+        let json: Value = serde_json::from_str(&text)?;
+        let exercise = json.get("exercise").ok_or(anyhow!("unexpected response, the exercise is missing"))?;
+        if !Self::is_team_exercise(exercise) {
+            bail!("task {} isn't a team exercise", taskid);
+        }
+
+        // once the team started the exercise its participation carries the team
+        let participations = exercise.get("studentParticipations").and_then(Value::as_array);
+        if let Some(team) = participations.into_iter().flatten().find_map(Self::parse_team) {
+            return Ok(team);
+        }
+        let team_id = exercise
+            .get("studentAssignedTeamId")
+            .and_then(Value::as_u64)
+            .ok_or(anyhow!("you aren't assigned to a team for task {} yet, ask your tutor", taskid))?;
+        let team_uri = format!("{}/api/exercises/{}/teams/{}", self.base_url, taskid, team_id);
+        let team: Value = self.fetch_json(&team_uri).await?.json().await?;
+        Self::parse_team_json(&team, None).ok_or(anyhow!("unexpected response, the team has no name"))
+    }
+
+    /// all results of the participation, oldest first
+    pub async fn get_result_history(
+        &mut self,
+        taskid: u64,
+        participation_type: ParticipationType,
+    ) -> Result<Vec<HistoryEntry>> {
+        let details_uri = format!("{}/api/exercises/{}/details", self.base_url, taskid);
+        let text = self.fetch_json(&details_uri).await?.text().await?;
+        Self::parse_result_history(&text, participation_type)
+    }
+
+    pub async fn get_submission_policy(&mut self, taskid: u64) -> Result<Option<SubmissionPolicy>> {
+        let details_uri = format!("{}/api/exercises/{}/details", self.base_url, taskid);
+        let text = self.fetch_json(&details_uri).await?.text().await?;
+        let json: Value = serde_json::from_str(&text)?;
+        let exercise = json.get("exercise").ok_or(anyhow!("the exercise details have no exercise"))?;
+        Ok(Self::parse_submission_policy(exercise))
+    }
+
+    /// a future that completes once artemis pushes a new result for `commit` over its websocket. it errors if the
+    /// server doesn't offer the websocket and may never complete if the socket stays silent, so callers have to
+    /// poll alongside it. it doesn't borrow the adapter, which stays usable for polling
+    pub fn result_push(&self, commit: &str) -> Result<impl Future<Output = Result<()>> + use<>> {
+        let url = reqwest::Url::parse(&self.base_url)?;
+        let ws_url = format!("{}/websocket", self.base_url.replacen("http", "ws", 1));
+        let mut request = ws_url.as_str().into_client_request()?;
+        if let Some(cookies) = self.cookies.cookies(&url) {
+            request.headers_mut().insert(reqwest::header::COOKIE, cookies);
+        }
+        if let Some(token) = &self.bearer_token {
+            request.headers_mut().insert(reqwest::header::AUTHORIZATION, format!("Bearer {}", token).parse()?);
+        }
+
+        let commit = commit.to_string();
+        Ok(async move {
+            let (mut socket, _) = tokio_tungstenite::connect_async(request).await?;
+            let host = url.host_str().unwrap_or_default();
+            socket.send(Message::text(Frame::connect(host).encode())).await?;
+            socket.send(Message::text(Frame::subscribe("results", RESULT_TOPIC).encode())).await?;
+            debug!("subscribed to {} on {}", RESULT_TOPIC, ws_url);
+
+            while let Some(message) = socket.next().await {
+                let Message::Text(text) = message? else { continue };
+                let Some(frame) = Frame::decode(&text)? else { continue };
+                match frame.command.as_str() {
+                    "ERROR" => bail!("artemis rejected the subscription: {}", frame.body),
+                    "MESSAGE" => {
+                        let result: Value = serde_json::from_str(&frame.body)?;
+                        let pushed_commit = result.pointer("/submission/commitHash").and_then(Value::as_str);
+                        // results without a commit can't be matched, let the caller check them
+                        if pushed_commit.is_none_or(|pushed| pushed == commit.as_str()) {
+                            return Ok(());
+                        }
+                        trace!("ignoring result for commit {:?}", pushed_commit);
+                    }
+                    _ => trace!("STOMP frame {}", frame.command),
+                }
+            }
+            bail!("artemis closed the websocket")
+        })
+    }
+
+    pub async fn get_latest_test_result(&mut self, taskid: u64, participation_type: ParticipationType) -> Result<TaskResult> {
+        self.get_test_result(taskid, participation_type, None).await
+    }
+
+    /// the build logs of the result `result_id`, or of the latest build of the participation which may
+    /// still be running
+    pub async fn get_build_logs(&mut self, participation_id: u64, result_id: Option<u64>) -> Result<Vec<LogStatement>> {
+        let mut buildlogs_url = format!("{}/api/repository/{}/buildlogs", self.base_url, participation_id);
+        if let Some(result_id) = result_id {
+            buildlogs_url.push_str(&format!("?resultId={}", result_id));
+        }
+        Ok(self.fetch_json(&buildlogs_url).await?.json().await?)
+    }
+
+    pub async fn get_build_state(&mut self, taskid: u64, participation_type: ParticipationType) -> Result<BuildState> {
+        let details_uri = format!("{}/api/exercises/{}/details", self.base_url, taskid);
+        let text = self.fetch_json(&details_uri).await?.text().await?;
+        Self::parse_build_state(&text, participation_type)
+    }
+
+    /// the result of the `attempt`-th submission (1-based, oldest first), or the latest one if `attempt` is None
+    pub async fn get_test_result(
+        &mut self,
+        taskid: u64,
+        participation_type: ParticipationType,
+        attempt: Option<usize>,
+    ) -> Result<TaskResult> {
         let details_uri = format!("{}/api/exercises/{}/details", self.base_url, taskid);
         let text = self
             .fetch_json(&details_uri)
@@ -223,119 +1152,588 @@ impl Adapter {
             .text()
             .await?;
 
-        let (participation_id, result_id, build_failiure) = Self::parse_exercise_details(&text).unwrap();
-
-        if build_failiure {
-            let buildlogs_url = format!(
-                "{}/api/repository/{}/buildlogs?resultId={}",
-                self.base_url, participation_id, result_id
-            );
+        let (participation_id, results) = Self::parse_exercise_details(&text, participation_type)?;
+        self.result_of_attempt(participation_id, results, attempt).await
+    }
 
-            let buildlogs: Vec<LogStatement> = self.fetch_json(&buildlogs_url).await?.json().await?;
+    /// the result with id `result_id` of the participation with the given id, or its latest one if `result_id` is
+    /// None, without looking the participation up in the exercise details first
+    pub async fn get_participation_test_result(
+        &mut self,
+        participation_id: u64,
+        result_id: Option<u64>,
+    ) -> Result<TaskResult> {
+        // a known result id doesn't need the list of results, so a cached result needs no request at all
+        if let Some(cached) = result_id.and_then(|result_id| self.cached_result(participation_id, result_id)) {
+            return Self::test_task_result(cached.result, &cached.details);
+        }
+        let results_uri = format!("{}/api/participations/{}/results?withSubmission=true", self.base_url, participation_id);
+        let text = self.fetch_json(&results_uri).await?.text().await?;
+        let json: Value = serde_json::from_str(&text)?;
+        let results = Self::parse_results(json.as_array().map(Vec::as_slice).unwrap_or_default(), None)?;
+        let attempt = match result_id {
+            Some(result_id) => match results.iter().position(|result| result.id == result_id) {
+                Some(index) => Some(index + 1),
+                None => bail!("participation {} has no result {}", participation_id, result_id),
+            },
+            None => None,
+        };
+        self.result_of_attempt(participation_id, results, attempt).await
+    }
 
-            println!("{}", "BUILD FAILIURE:".red().bold());
-            for log in buildlogs {
-                println!("{}", log);
+    /// fetches the tests or build logs of the `attempt`-th of `results`, the latest one if `attempt` is None
+    async fn result_of_attempt(
+        &mut self,
+        participation_id: u64,
+        mut results: Vec<ResultInfo>,
+        attempt: Option<usize>,
+    ) -> Result<TaskResult> {
+        let result = match attempt {
+            Some(attempt) if attempt == 0 || attempt > results.len() => {
+                bail!("there is no attempt {}, this participation has {} results", attempt, results.len())
             }
+            Some(attempt) => results.swap_remove(attempt - 1),
+            None => results.pop().ok_or(anyhow!("there are no results available yet"))?,
+        };
 
-            return Ok(Vec::new());
+        if result.build_failed {
+            let buildlogs = self.get_build_logs(participation_id, Some(result.id)).await?;
+            return Ok(TaskResult {
+                completion_date: result.completion_date,
+                commit_hash: result.commit_hash,
+                score: result.score,
+                outcome: TestOutcome::BuildFailure(buildlogs),
+                static_analysis: Vec::new(),
+                feedback: Vec::new(),
+                assessment: Vec::new(),
+            });
         }
 
+        if let Some(cached) = self.cached_result(participation_id, result.id) {
+            return Self::test_task_result(result, &cached.details);
+        }
         let test_result_uri = format!(
             "{}/api/participations/{}/results/{}/details",
-            self.base_url, participation_id, result_id,
+            self.base_url, participation_id, result.id,
         );
 
         let test_result_text = self.fetch_json(&test_result_uri).await?.text().await?;
+        let task_result = Self::test_task_result(result.clone(), &test_result_text)?;
+        let cached = CachedResult {
+            result,
+            details: test_result_text,
+        };
+        if let Err(e) = ResultCache::save(participation_id, &cached) {
+            warn!("can't cache the result: {:#}", e);
+        }
+        Ok(task_result)
+    }
+
+    /// downloads result details again instead of reading them from the cache, e.g. for fetch --refresh
+    pub fn bypass_result_cache(&mut self) {
+        self.use_result_cache = false;
+    }
+
+    /// the cached details of a result, None if there are none or the cache is bypassed
+    fn cached_result(&self, participation_id: u64, result_id: u64) -> Option<CachedResult> {
+        let cached = self.use_result_cache.then(|| ResultCache::load(participation_id, result_id)).flatten()?;
+        debug!("using the cached details of result {}", result_id);
+        Some(cached)
+    }
 
-        Self::parse_test_result_details(test_result_text.to_owned())
+    /// the result of a build that ran the tests, from the response of the result details
+    fn test_task_result(result: ResultInfo, details: &str) -> Result<TaskResult> {
+        let details = Self::parse_test_result_details(details.to_owned())?;
+        Ok(TaskResult {
+            completion_date: result.completion_date,
+            commit_hash: result.commit_hash,
+            score: result.score,
+            outcome: TestOutcome::TestResults(details.tests),
+            static_analysis: details.static_analysis,
+            feedback: details.feedback,
+            assessment: details.assessment,
+        })
     }
 
-    pub async fn start_artemis_task(&mut self, taskid: u64) -> Result<String> {
-        let participations_url = format!("{}/api/exercises/{}/participations", self.base_url, taskid);
+    /// starts the participation and returns the ssh url of its repository, or of the auxiliary repository
+    /// named `template` if one is given
+    pub async fn start_artemis_task(
+        &mut self,
+        taskid: u64,
+        participation_type: ParticipationType,
+        template: Option<&str>,
+    ) -> Result<String> {
+        let participations_url = match participation_type {
+            ParticipationType::Graded => format!("{}/api/exercises/{}/participations", self.base_url, taskid),
+            ParticipationType::Practice => format!("{}/api/exercises/{}/participations/practice", self.base_url, taskid),
+        };
         let response = self
-            .client
-            .post(&participations_url)
+            .authorized(self.client.post(&participations_url))
             .header("Accept", "application/json")
             .send()
-            .await?;
+            .await
+            .map_err(|e| self.connection_error(e))?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             self.login().await?;
         }
 
         if !response.status().is_success() {
-            error!("coudn't start new task {} ", response.status());
-            return Err(anyhow!("coudn't start new task {}", response.status()));
+            let status = response.status();
+            error!("coudn't start new task {} ", status);
+            // team exercises can only be started once a tutor assigned the student to a team
+            let reason = Self::parse_error_reason(&response.text().await.unwrap_or_default());
+            if let Some(reason) = reason.filter(|reason| reason.to_lowercase().contains("team")) {
+                bail!("coudn't start new task: {}", reason);
+            }
+            return Err(anyhow!("coudn't start new task {}", status));
         }
 
+        // for team exercises this is the participation of the team, so its repository is the shared one
         let text = response.text().await.expect("cant read response body");
         let mut deserializer = serde_json::Deserializer::from_str(&text);
         let json = Value::deserialize(&mut deserializer)?;
 
-        let repo_uri = json.get("repositoryUri").unwrap().to_string();
-        let suffix = repo_uri.split_once("@").expect("uri didn't contain '@'").1;
-        let mut prefix = "ssh://git@".to_string();
-        prefix.push_str(suffix);
+        let repo_uri = match template {
+            None => json_str(&json, "repositoryUri")?,
+            Some(name) => Self::auxiliary_repository_uri(&json, name)?,
+        };
+        Ok(Self::clone_uri(&repo_uri, self.clone_protocol))
+    }
+
+    /// ssh uris are used as they are. https uris are cloned over https authenticated by the session, so students
+    /// without ssh keys can clone, or over ssh if the user chose to with the clone-protocol config
+    fn clone_uri(repo_uri: &str, protocol: CloneProtocol) -> String {
+        if !repo_uri.starts_with("https://") && !repo_uri.starts_with("http://") {
+            return repo_uri.to_string();
+        }
+        if protocol == CloneProtocol::Ssh
+            && let Some(ssh_uri) = Self::ssh_uri(repo_uri)
+        {
+            return ssh_uri;
+        }
+        // the user name in the uri would make git ask for a password instead of sending the session
+        match repo_uri.split_once("://").zip(repo_uri.split_once("@")) {
+            Some(((scheme, _), (_, host))) => format!("{}://{}", scheme, host),
+            None => repo_uri.to_string(),
+        }
+    }
+
+    /// the headers that authenticate git requests to artemis over https with the current session, restricted to
+    /// the host of artemis
+    pub fn git_http_auth(&self) -> HttpAuth {
+        let headers = if let Some(token) = &self.bearer_token {
+            vec![format!("Authorization: Bearer {}", token)]
+        } else {
+            let url = reqwest::Url::parse(self.base_url.as_str()).unwrap();
+            match self.cookies.cookies(&url).as_ref().and_then(|cookies| cookies.to_str().ok()) {
+                Some(cookies) => vec![format!("Cookie: {}", cookies)],
+                None => Vec::new(),
+            }
+        };
+        HttpAuth::new(&self.base_url, headers)
+    }
+
+    /// artemis returns http urls with the user name, e.g. `https://user@host/git/repo.git`, we clone over ssh.
+    /// None for urls without a user name, those can only be cloned as they are
+    fn ssh_uri(repo_uri: &str) -> Option<String> {
+        let suffix = repo_uri.split_once("@")?.1;
+        Some(format!("ssh://git@{}", suffix))
+    }
+
+    /// the example solution of an exercise, None if it hasn't been released yet
+    pub async fn get_example_solution(&mut self, taskid: u64) -> Result<Option<ExampleSolution>> {
+        let solution_uri = format!("{}/api/exercises/{}/example-solution", self.base_url, taskid);
+        let client = self.client.clone();
+        let request = || client.get(&solution_uri).header("Accept", "application/json");
+        let mut response = self.send(request()).await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.login().await?;
+            response = self.send(request()).await?;
+        }
+        // artemis denies access to the solution until it is published
+        if matches!(response.status(), StatusCode::FORBIDDEN | StatusCode::NOT_FOUND) {
+            debug!("example solution of {}: {}", taskid, response.status());
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            bail!("coudn't fetch the example solution: {}", response.status());
+        }
+        Self::parse_example_solution(&response.text().await?, self.clone_protocol)
+    }
 
-        Ok(prefix)
+    fn parse_example_solution(text: &str, protocol: CloneProtocol) -> Result<Option<ExampleSolution>> {
+        let json: Value = serde_json::from_str(text)?;
+        let exercise = json.get("exercise").unwrap_or(&json);
+        if let Some(uri) = exercise.pointer("/solutionParticipation/repositoryUri").and_then(Value::as_str) {
+            return Ok(Some(ExampleSolution::Repository(Self::clone_uri(uri, protocol))));
+        }
+        match exercise.get("exampleSolution").and_then(Value::as_str) {
+            Some(solution) if !solution.is_empty() => Ok(Some(ExampleSolution::Text(solution.to_string()))),
+            _ => Ok(None),
+        }
+    }
+
+    /// the url of the auxiliary repository called `name` of the exercise the participation belongs to
+    fn auxiliary_repository_uri(participation: &Value, name: &str) -> Result<String> {
+        let repositories = participation
+            .pointer("/exercise/auxiliaryRepositories")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let names: Vec<String> = repositories.iter().filter_map(|repo| json_str(repo, "name").ok()).collect();
+        match repositories.iter().find(|repo| json_str(repo, "name").is_ok_and(|n| n.eq_ignore_ascii_case(name))) {
+            Some(repo) => json_str(repo, "repositoryUri"),
+            None if names.is_empty() => bail!("this exercise has no repository '{}', it only has the student one", name),
+            None => bail!("this exercise has no repository '{}', available are: {}", name, names.join(", ")),
+        }
+    }
+
+    /// uploads `path` as submission for a file upload exercise and returns the id of the created submission
+    pub async fn submit_file(&mut self, taskid: u64, path: &Path) -> Result<u64> {
+        let details_uri = format!("{}/api/exercises/{}/details", self.base_url, taskid);
+        let details = self.fetch_json(&details_uri).await?.text().await?;
+        let allowed_extensions = Self::parse_file_pattern(&details)?;
+
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        if !allowed_extensions.is_empty() && !allowed_extensions.contains(&extension) {
+            return Err(anyhow!(
+                "{} has the wrong file type, this exercise only accepts: {}",
+                path.display(),
+                allowed_extensions.join(", ")
+            ));
+        }
+
+        let size = fs::metadata(path)?.len();
+        if size > MAX_UPLOAD_SIZE {
+            return Err(anyhow!(
+                "{} is {} bytes, artemis only accepts files up to {} bytes",
+                path.display(),
+                size,
+                MAX_UPLOAD_SIZE
+            ));
+        }
+
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or(anyhow!("{} is not a valid file name", path.display()))?
+            .to_string();
+        let file_part = Part::bytes(fs::read(path)?).file_name(file_name).mime_str("application/octet-stream")?;
+        let submission_part =
+            Part::text(json!({ "submissionExerciseType": "file-upload" }).to_string()).mime_str("application/json")?;
+        let form = Form::new().part("file", file_part).part("submission", submission_part);
+
+        let upload_uri = format!("{}/api/exercises/{}/file-upload-submissions?submit=true", self.base_url, taskid);
+        debug!("uploading {} to {}", path.display(), upload_uri);
+        let response = self
+            .authorized(self.client.post(&upload_uri))
+            .header("Accept", "application/json")
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| self.connection_error(e))?;
+
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            let reason = Self::parse_error_reason(&text).unwrap_or(status.to_string());
+            error!("artemis rejected the file upload: {}", reason);
+            return Err(anyhow!("artemis rejected the file upload: {}", reason));
+        }
+
+        let json: Value = serde_json::from_str(&text)?;
+        json.get("id")
+            .and_then(Value::as_u64)
+            .ok_or(anyhow!("artemis didn't return the id of the submission"))
     }
 }
 
 impl Adapter {
-    fn parse_task(raw_task: &Value) -> Result<Task> {
-        let task_id = raw_task.get("id").unwrap().as_u64().unwrap();
-        let task_title = raw_task.get("title").unwrap().to_string();
-        let active = raw_task.get("studentParticipations");
-
-        if active.is_none() {
-            let task = Task {
-                is_active: false,
-                completed: false,
-                id: task_id,
-                title: task_title,
-            };
-            return Ok(task);
+    /// extracts the human readable reason from an artemis error response
+    fn parse_error_reason(text: &str) -> Option<String> {
+        let json: Value = serde_json::from_str(text).ok()?;
+        ["detail", "title", "message"]
+            .iter()
+            .find_map(|field| json_str(&json, field).ok())
+    }
+
+    /// reads the allowed file extensions of a file upload exercise from its details
+    fn parse_file_pattern(text: &str) -> Result<Vec<String>> {
+        let json: Value = serde_json::from_str(text)?;
+        let pattern = json
+            .get("exercise")
+            .and_then(|exercise| json_str(exercise, "filePattern").ok())
+            .ok_or(anyhow!("this is not a file upload exercise"))?;
+
+        Ok(pattern
+            .split(',')
+            .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+            .filter(|extension| !extension.is_empty())
+            .collect())
+    }
+
+    fn parse_account(text: &str) -> Result<Account> {
+        let json: Value = serde_json::from_str(text)?;
+        let field = |name: &str| json_str(&json, name).ok();
+
+        let login = json_str(&json, "login")?;
+        let name = field("name")
+            .or_else(|| {
+                let full_name =
+                    [field("firstName"), field("lastName")].into_iter().flatten().collect::<Vec<_>>().join(" ");
+                (!full_name.is_empty()).then_some(full_name)
+            })
+            .unwrap_or(login.clone());
+        let email = json_str(&json, "email")?;
+
+        Ok(Account { login, name, email })
+    }
+
+    fn parse_hint_ids(text: &str) -> Result<Vec<u64>> {
+        let json: Value = serde_json::from_str(text)?;
+        let raw_hints = json.as_array().ok_or(anyhow!("exercise hints are not a list"))?;
+        Ok(raw_hints.iter().filter_map(|hint| hint.get("id").and_then(Value::as_u64)).collect())
+    }
+
+    fn parse_exercise_hints(text: &str, available: Option<&[u64]>) -> Result<Vec<ExerciseHint>> {
+        let json: Value = serde_json::from_str(text)?;
+        let raw_hints = json.as_array().ok_or(anyhow!("exercise hints are not a list"))?;
+
+        let mut hints = Vec::new();
+        for raw_hint in raw_hints {
+            let id = raw_hint.get("id").and_then(Value::as_u64).ok_or(anyhow!("exercise hint has no id"))?;
+            let available = available.is_none_or(|available| available.contains(&id));
+            hints.push(ExerciseHint {
+                id,
+                title: json_str(raw_hint, "title")?,
+                content: available
+                    .then(|| json_str(raw_hint, "content").or_else(|_| json_str(raw_hint, "description")).ok())
+                    .flatten(),
+                available,
+            });
         }
+        Ok(hints)
+    }
 
-        let participation_info = raw_task
-            .get("studentParticipations")
-            .unwrap()
+    /// the posts are either a plain list or a page with the posts as `content`. older artemis versions mark
+    /// announcements with a `courseWideContext`, newer ones post them to an announcement channel
+    fn parse_announcements(text: &str) -> Result<Vec<Announcement>> {
+        let json: Value = serde_json::from_str(text)?;
+        let posts = json
             .as_array()
-            .unwrap()
-            .first()
-            .unwrap();
-
-        if participation_info.get("results").is_none() {
-            let task = Task {
-                title: task_title,
-                id: task_id,
-                completed: false,
-                is_active: true,
-            };
-            return Ok(task);
+            .or(json.get("content").and_then(Value::as_array))
+            .ok_or(anyhow!("unexpected response, the posts are not a list"))?;
+
+        let is_announcement = |post: &Value| {
+            let context = post.get("courseWideContext").and_then(Value::as_str);
+            let channel = post
+                .get("conversation")
+                .and_then(|conversation| conversation.get("isAnnouncementChannel"))
+                .and_then(Value::as_bool);
+            match (context, channel) {
+                (None, None) => true,
+                (context, channel) => context == Some("ANNOUNCEMENT") || channel == Some(true),
+            }
+        };
+        let mut announcements: Vec<Announcement> = posts
+            .iter()
+            .filter(|post| is_announcement(post))
+            .map(|post| Announcement {
+                title: json_str(post, "title").ok().filter(|title| !title.is_empty()),
+                author: post.get("author").and_then(|author| json_str(author, "name").ok()),
+                date: post
+                    .get("creationDate")
+                    .and_then(Value::as_str)
+                    .and_then(|date| DateTime::parse_from_rfc3339(date).ok()),
+                content: json_str(post, "content").unwrap_or_default(),
+            })
+            .collect();
+        announcements.sort_by_key(|announcement| Reverse(announcement.date));
+        Ok(announcements)
+    }
+
+    fn parse_competencies(text: &str) -> Result<Vec<Competency>> {
+        let json: Value = serde_json::from_str(text)?;
+        let raw_competencies = json.as_array().ok_or(anyhow!("competencies are not a list"))?;
+
+        let mut competencies = Vec::new();
+        for raw_competency in raw_competencies {
+            let progress = raw_competency
+                .get("userProgress")
+                .and_then(Value::as_array)
+                .and_then(|progress| progress.first())
+                .and_then(|progress| progress.get("progress"))
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0);
+
+            competencies.push(Competency {
+                id: raw_competency.get("id").and_then(Value::as_u64).ok_or(anyhow!("competency has no id"))?,
+                title: json_str(raw_competency, "title")?,
+                progress,
+                mastery_threshold: raw_competency.get("masteryThreshold").and_then(Value::as_f64),
+            });
         }
+        Ok(competencies)
+    }
 
-        let completed = participation_info
-            .get("results")
-            .unwrap()
-            .as_array()
-            .unwrap()
+    fn parse_task(raw_task: &Value) -> Result<Task> {
+        let task_id = raw_task.get("id").unwrap().as_u64().unwrap();
+        let task_title = json_str(raw_task, "title")?;
+        let date = |field: &str| {
+            raw_task
+                .get(field)
+                .and_then(Value::as_str)
+                .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        };
+
+        // the array is empty for exercises that were started and then reset
+        let participation_info = raw_task.get("studentParticipations").and_then(|participations| {
+            let participations = participations.as_array()?;
+            Self::select_participation(participations, ParticipationType::Graded).or(participations.first())
+        });
+
+        let results = participation_info
+            .and_then(|participation| participation.get("results"))
+            .map(|results| results.as_array().unwrap().as_slice())
+            .unwrap_or_default();
+        let max_points = raw_task.get("maxPoints").and_then(Value::as_f64);
+        // full points count as completed, even if the percentage isn't exactly 100 after rounding
+        let completed = results
+            .iter()
+            .any(|result| Self::result_score(result, max_points).is_some_and(|score| score >= 100.0));
+        let latest_score = results
             .iter()
-            .fold(false, |acc, e| acc | (e.get("score").unwrap().as_f64().unwrap() == 100.0));
+            .max_by_key(|result| result.get("completionDate").and_then(Value::as_str))
+            .and_then(|result| Self::result_score(result, max_points));
 
-        let task = Task {
+        Ok(Task {
             title: task_title,
             id: task_id,
             completed,
-            is_active: true,
+            is_active: participation_info.is_some(),
+            participation_id: raw_task
+                .get("studentParticipations")
+                .and_then(Value::as_array)
+                .and_then(|participations| Self::select_participation(participations, ParticipationType::Graded))
+                .and_then(|participation| participation.get("id"))
+                .and_then(Value::as_u64),
+            due_date: date("dueDate"),
+            release_date: date("releaseDate"),
+            categories: Self::parse_categories(raw_task),
+            submission_policy: Self::parse_submission_policy(raw_task),
+            difficulty: raw_task.get("difficulty").and_then(Value::as_str).and_then(Difficulty::parse),
+            max_points,
+            result_count: results.len(),
+            latest_score,
+            prerequisites: Self::parse_prerequisites(raw_task),
+            team_mode: Self::is_team_exercise(raw_task),
+        })
+    }
+
+    fn is_team_exercise(raw_exercise: &Value) -> bool {
+        raw_exercise.get("mode").and_then(Value::as_str) == Some("TEAM")
+    }
+
+    /// the team of a team participation, None for the participation of a single student
+    fn parse_team(participation: &Value) -> Option<Team> {
+        let team = participation.get("team").filter(|team| team.is_object())?;
+        Self::parse_team_json(team, participation.get("repositoryUri").and_then(Value::as_str))
+    }
+
+    fn parse_team_json(team: &Value, repository: Option<&str>) -> Option<Team> {
+        let members = team
+            .get("students")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|student| {
+                Some(Account {
+                    login: json_str(student, "login").ok()?,
+                    name: json_str(student, "name").unwrap_or_default(),
+                    email: json_str(student, "email").unwrap_or_default(),
+                })
+            })
+            .collect();
+        Some(Team {
+            name: json_str(team, "name").ok()?,
+            short_name: json_str(team, "shortName").unwrap_or_default(),
+            members,
+            repository: repository.map(str::to_string),
+        })
+    }
+
+    /// the score of a result in percent. some payloads only have the `achievedPoints`, then the score is computed
+    /// from the points of the result or else the `max_points` of the exercise
+    fn result_score(result: &Value, max_points: Option<f64>) -> Option<f64> {
+        if let Some(score) = result.get("score").and_then(Value::as_f64) {
+            return Some(score);
+        }
+        let achieved = result.get("achievedPoints").and_then(Value::as_f64)?;
+        let max_points = result
+            .get("maxPoints")
+            .and_then(Value::as_f64)
+            .or(max_points)
+            .filter(|max_points| *max_points > 0.0)?;
+        Some(achieved / max_points * 100.0)
+    }
+
+    /// prerequisites are given either as plain exercise ids or as exercise objects
+    fn parse_prerequisites(raw_task: &Value) -> Vec<u64> {
+        raw_task
+            .get("prerequisites")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|prerequisite| prerequisite.as_u64().or(prerequisite.get("id").and_then(Value::as_u64)))
+            .collect()
+    }
+
+    /// the policy of a programming exercise, None if there is none or it isn't active
+    fn parse_submission_policy(raw_exercise: &Value) -> Option<SubmissionPolicy> {
+        let policy = raw_exercise.get("submissionPolicy").filter(|policy| !policy.is_null())?;
+        if policy.get("active").and_then(Value::as_bool) == Some(false) {
+            return None;
+        }
+
+        let limit = policy.get("submissionLimit").and_then(Value::as_u64)?;
+        match policy.get("type").and_then(Value::as_str)? {
+            "submission_penalty" => Some(SubmissionPolicy::Penalty {
+                limit,
+                penalty: policy.get("exceedingPenalty").and_then(Value::as_f64).unwrap_or(0.0),
+            }),
+            "lock_repository" => Some(SubmissionPolicy::LockRepository { limit }),
+            other => {
+                debug!("unknown submission policy type {}", other);
+                None
+            }
+        }
+    }
+
+    /// older artemis versions send each category as a JSON encoded string, newer ones as an object
+    fn parse_categories(raw_task: &Value) -> Vec<String> {
+        let Some(raw_categories) = raw_task.get("categories").and_then(Value::as_array) else {
+            return Vec::new();
         };
-        return Ok(task);
+        raw_categories
+            .iter()
+            .filter_map(|raw_category| match raw_category {
+                Value::String(encoded) => serde_json::from_str::<Value>(encoded)
+                    .ok()
+                    .and_then(|category| json_str(&category, "category").ok())
+                    .or(Some(encoded.clone())),
+                category => json_str(category, "category").ok(),
+            })
+            .collect()
     }
 
     fn parse_course(course: &Value) -> Result<Course> {
         trace!("parsing course ... ");
-        let course_title = course.get("title").unwrap().to_string();
+        let course_title = json_str(course, "title")?;
 
         let course_id = course.get("id").unwrap().as_u64().unwrap();
 
@@ -344,68 +1742,476 @@ impl Adapter {
 
         trace!("fetching {} tasks...", raw_tasks.len());
         for raw_task in raw_tasks {
-            tasks.push(Self::parse_task(raw_task).unwrap());
+            tasks.push(Self::parse_task(raw_task)?);
         }
 
+        let optional_str = |field: &str| json_str(course, field).ok().filter(|value| !value.is_empty());
         Ok(Course {
             id: course_id,
             title: course_title,
+            short_name: optional_str("shortName"),
+            semester: optional_str("semester"),
             tasks,
         })
     }
-    fn parse_exercise_details(text: &str) -> Result<(u64, u64, bool)> {
+
+    /// the participations of the student, errors if there are none, e.g. because the exercise was reset
+    fn student_participations(exercise: &Value) -> Result<&[Value]> {
+        match exercise.get("studentParticipations").and_then(Value::as_array) {
+            Some(participations) if !participations.is_empty() => Ok(participations),
+            _ if Self::is_team_exercise(exercise) => {
+                bail!("your team has no active participation in this exercise, run 'artemis-cli start-task' first")
+            }
+            _ => bail!("you have no active participation in this exercise, run 'artemis-cli start-task' first"),
+        }
+    }
+
+    /// picks the participation of the given type, participations without a `testRun` flag count as graded
+    fn select_participation(participations: &[Value], participation_type: ParticipationType) -> Option<&Value> {
+        let practice = participation_type == ParticipationType::Practice;
+        participations
+            .iter()
+            .find(|participation| participation.get("testRun").and_then(Value::as_bool).unwrap_or(false) == practice)
+    }
+
+    /// the id of the participation and its results, oldest first
+    fn parse_exercise_details(text: &str, participation_type: ParticipationType) -> Result<(u64, Vec<ResultInfo>)> {
         let mut deserializer = serde_json::Deserializer::from_str(text);
         let json = Value::deserialize(&mut deserializer)?;
         let exercise = json.get("exercise").unwrap();
-        let participation = exercise
-            .get("studentParticipations")
-            .unwrap()
-            .as_array()
-            .unwrap()
-            .first()
-            .unwrap();
+        let participations = Self::student_participations(exercise)?;
+        let participation = Self::select_participation(participations, participation_type)
+            .ok_or(anyhow!("you have no {} participation in this exercise", participation_type))?;
 
         let participation_id = participation.get("id").unwrap().as_u64().unwrap();
         let results = participation
             .get("results")
-            .expect("there are no results available yet")
-            .as_array()
-            .unwrap();
+            .and_then(Value::as_array)
+            .ok_or(anyhow!("there are no results available yet"))?;
+
+        let results = Self::parse_results(results, exercise.get("maxPoints").and_then(Value::as_f64))?;
+        Ok((participation_id, results))
+    }
 
+    /// the finished results that belong to a submission, oldest first. fails with `BuildStillRunning` if there
+    /// are only results whose build hasn't completed yet
+    fn parse_results(results: &[Value], max_points: Option<f64>) -> Result<Vec<ResultInfo>> {
         let mut submissions = Vec::new();
+        let mut unfinished = 0;
         for result in results {
             let result_id = result.get("id").unwrap().as_u64().unwrap();
-            let completion_time = result.get("completionDate").unwrap().as_str().unwrap();
-            let timestamp = DateTime::parse_from_rfc3339(completion_time).unwrap();
+            // the completion date stays null while the build is running
+            let Some(timestamp) = Self::completion_date(result)? else {
+                debug!("skipping unfinished result {}", result_id);
+                unfinished += 1;
+                continue;
+            };
 
-            let build_failiure = result.get("submission").unwrap().get("buildFailed").unwrap().as_bool().unwrap();
+            let Some(submission) = result.get("submission").filter(|submission| submission.is_object()) else {
+                debug!("skipping result {} without a submission", result_id);
+                continue;
+            };
+            // manual and quiz submissions are never built
+            let build_failiure = submission.get("buildFailed").and_then(Value::as_bool).unwrap_or(false);
+            let commit_hash = submission.get("commitHash").and_then(Value::as_str).map(str::to_string);
+            let score = Self::result_score(result, max_points);
+
+            submissions.push(ResultInfo {
+                id: result_id,
+                completion_date: timestamp,
+                build_failed: build_failiure,
+                score,
+                commit_hash,
+            });
+        }
+        if submissions.is_empty() && unfinished > 0 {
+            return Err(BuildStillRunning.into());
+        }
+        if submissions.is_empty() {
+            bail!("there are no results available yet");
+        }
+        submissions.sort_by_key(|result| result.completion_date);
+        Ok(submissions)
+    }
 
-            submissions.push((timestamp, result_id, build_failiure));
+    /// when the result was completed, None if it is null or missing because the build is still running
+    fn completion_date(result: &Value) -> Result<Option<DateTime<FixedOffset>>> {
+        match result.get("completionDate").and_then(Value::as_str) {
+            Some(date) => Ok(Some(DateTime::parse_from_rfc3339(date)?)),
+            None => Ok(None),
         }
-        let (_, resutl_id, build_faliure) = submissions.iter().max_by(|(ts1, _, _), (ts2, _, _)| ts1.cmp(ts2)).unwrap();
+    }
 
-        Ok((participation_id, *resutl_id, *build_faliure))
+    fn parse_build_state(text: &str, participation_type: ParticipationType) -> Result<BuildState> {
+        let json: Value = serde_json::from_str(text)?;
+        let participations = json
+            .get("exercise")
+            .ok_or(anyhow!("unexpected response, the exercise is missing"))
+            .and_then(Self::student_participations)?;
+        let participation = Self::select_participation(participations, participation_type)
+            .ok_or(anyhow!("you have no {} participation in this exercise", participation_type))?;
+
+        let participation_id = participation
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or(anyhow!("unexpected response, the participation has no id"))?;
+        let results = participation.get("results").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+        let latest_result = results.iter().filter_map(|result| result.get("id").and_then(Value::as_u64)).max();
+
+        // a build is pending while its result has no completion date or a submission has no result at all
+        let result_submissions: Vec<u64> = results
+            .iter()
+            .filter_map(|result| result.get("submission")?.get("id")?.as_u64())
+            .collect();
+        let unanswered_submission = participation
+            .get("submissions")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|submission| submission.get("id").and_then(Value::as_u64))
+            .any(|id| !result_submissions.contains(&id));
+        let unfinished_result = results.iter().any(|result| Self::completion_date(result).ok().flatten().is_none());
+
+        Ok(BuildState {
+            participation_id,
+            latest_result,
+            pending: unanswered_submission || unfinished_result,
+        })
     }
 
-    fn parse_test_result_details(text: String) -> Result<Vec<Test>> {
+    fn parse_result_history(text: &str, participation_type: ParticipationType) -> Result<Vec<HistoryEntry>> {
+        let json: Value = serde_json::from_str(text)?;
+        let exercise = json.get("exercise").ok_or(anyhow!("unexpected response, the exercise is missing"))?;
+        let participations = Self::student_participations(exercise)?;
+        let participation = Self::select_participation(participations, participation_type)
+            .ok_or(anyhow!("you have no {} participation in this exercise", participation_type))?;
+        let max_points = exercise.get("maxPoints").and_then(Value::as_f64);
+
+        let mut history = Vec::new();
+        for result in participation.get("results").and_then(Value::as_array).into_iter().flatten() {
+            let Some(date) = Self::completion_date(result)? else { continue };
+            history.push(HistoryEntry {
+                date,
+                score: Self::result_score(result, max_points),
+                passed: result.get("passedTestCaseCount").and_then(Value::as_u64),
+                total: result.get("testCaseCount").and_then(Value::as_u64),
+            });
+        }
+        history.sort_by_key(|entry| entry.date);
+        Ok(history)
+    }
+
+    /// splits the feedbacks of a result into test results and static code analysis issues
+    fn parse_test_result_details(text: String) -> Result<ResultDetails> {
         let mut deserializer = serde_json::Deserializer::from_str(&text);
         let json = Value::deserialize(&mut deserializer)?;
         let raw_tests = json.as_array().unwrap();
 
         let mut tests = Vec::new();
+        let mut static_analysis = Vec::new();
+        let mut feedback = Vec::new();
+        let mut assessment = Vec::new();
 
         for raw_test in raw_tests {
+            if let Some(entry) = Self::parse_assessment_feedback(raw_test) {
+                assessment.push(entry);
+                continue;
+            }
+            if Self::is_static_analysis_feedback(raw_test) {
+                match Self::parse_static_analysis_issue(raw_test) {
+                    Some(issue) => static_analysis.push(issue),
+                    None => debug!("can't parse static code analysis feedback: {}", raw_test),
+                }
+                continue;
+            }
+
+            // only automatic feedback of a test case has a `testCase`, everything else is textual feedback
+            if raw_test.get("testCase").is_none_or(Value::is_null) {
+                match Self::parse_text_feedback(raw_test) {
+                    Some(text_feedback) => feedback.push(text_feedback),
+                    None => debug!("ignoring feedback without text: {}", raw_test),
+                }
+                continue;
+            }
+
             let passed = raw_test.get("positive").unwrap().as_bool().unwrap();
-            let name = raw_test.get("testCase").unwrap().get("testName").unwrap().to_string();
-            let explanation = if !passed {
-                Some(raw_test.get("detailText").unwrap().to_string())
-            } else {
-                None
-            };
+            let name = json_str(raw_test.get("testCase").unwrap(), "testName")?;
+            let explanation = if !passed { json_str(raw_test, "detailText").ok() } else { None };
             let test = Test { name, passed, explanation };
             tests.push(test);
         }
 
-        Ok(tests)
+        Ok(ResultDetails {
+            tests,
+            static_analysis,
+            feedback,
+            assessment,
+        })
+    }
+
+    /// feedback of the manual assessment references either a grading instruction or a line of the submission,
+    /// None for any other feedback
+    fn parse_assessment_feedback(feedback: &Value) -> Option<AssessmentFeedback> {
+        let instruction = feedback.get("gradingInstruction").filter(|instruction| instruction.is_object());
+        let reference = feedback.get("reference").and_then(Value::as_str);
+        let criterion = match (instruction, reference) {
+            (Some(instruction), _) => instruction
+                .get("gradingCriterion")
+                .and_then(|criterion| json_str(criterion, "title").ok())
+                .or(json_str(instruction, "gradingScale").ok())
+                .or(json_str(instruction, "instructionDescription").ok())
+                .unwrap_or("grading instruction".to_string()),
+            // e.g. `file:src/Main.java_line:12`
+            (None, Some(reference)) => match reference.strip_prefix("file:").and_then(|file| file.rsplit_once("_line:")) {
+                Some((file, line)) => format!("{}:{}", file, line),
+                None => reference.to_string(),
+            },
+            (None, None) => return None,
+        };
+        let comment = json_str(feedback, "detailText")
+            .ok()
+            .or(instruction.and_then(|instruction| json_str(instruction, "feedback").ok()))
+            .filter(|comment| !comment.is_empty());
+        Some(AssessmentFeedback {
+            criterion,
+            points: feedback.get("credits").and_then(Value::as_f64),
+            comment,
+        })
+    }
+
+    fn parse_text_feedback(feedback: &Value) -> Option<TextFeedback> {
+        let title = json_str(feedback, "text").ok().filter(|title| !title.is_empty());
+        let text = json_str(feedback, "detailText").ok().filter(|text| !text.is_empty());
+        let (title, text) = match (title, text) {
+            (title, Some(text)) => (title, text),
+            (Some(title), None) => (None, title),
+            (None, None) => return None,
+        };
+        Some(TextFeedback {
+            kind: json_str(feedback, "type").unwrap_or("MANUAL".to_string()),
+            title,
+            text,
+        })
+    }
+
+    fn is_static_analysis_feedback(feedback: &Value) -> bool {
+        feedback.get("type").and_then(Value::as_str) == Some("Static Code Analysis")
+            || feedback
+                .get("text")
+                .and_then(Value::as_str)
+                .is_some_and(|text| text.starts_with("SCAFeedbackIdentifier"))
+    }
+
+    /// the issue itself is a JSON object encoded in the `detailText` of the feedback
+    fn parse_static_analysis_issue(feedback: &Value) -> Option<StaticAnalysisIssue> {
+        let detail: Value = serde_json::from_str(&json_str(feedback, "detailText").ok()?).ok()?;
+        let field = |name: &str| json_str(&detail, name).ok();
+        Some(StaticAnalysisIssue {
+            file: field("filePath")?,
+            line: detail.get("startLine").and_then(Value::as_u64),
+            rule: field("rule").unwrap_or_default(),
+            message: field("message").unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use chrono::DateTime;
+
+    use super::{
+        Adapter, BuildState, BuildStillRunning, CloneProtocol, ExampleSolution, LogStatement, ParticipationType,
+        TestOutcome,
+    };
+
+    fn result(id: u64, completion_date: Value) -> Value {
+        json!({
+            "id": id,
+            "completionDate": completion_date,
+            "score": 50.0,
+            "submission": { "commitHash": "abc", "buildFailed": false },
+        })
+    }
+
+    #[test]
+    fn unfinished_result_is_skipped_next_to_finished_one() {
+        let results = [result(1, json!("2025-04-01T12:00:00+02:00")), result(2, Value::Null)];
+        let parsed = Adapter::parse_results(&results, None).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].id, 1);
+    }
+
+    #[test]
+    fn only_unfinished_results_report_running_build() {
+        let mut missing = result(2, Value::Null);
+        missing.as_object_mut().unwrap().remove("completionDate");
+        let error = Adapter::parse_results(&[result(1, Value::Null), missing], None).unwrap_err();
+        assert!(error.downcast_ref::<BuildStillRunning>().is_some());
+    }
+
+    #[test]
+    fn manual_submission_without_build_failed_is_parsed() {
+        let details = json!({
+            "exercise": {
+                "maxPoints": 10.0,
+                "studentParticipations": [{
+                    "id": 42,
+                    "results": [
+                        {
+                            "id": 1,
+                            "completionDate": "2025-04-01T12:00:00+02:00",
+                            "score": 80.0,
+                            "submission": { "submissionExerciseType": "text" },
+                        },
+                        // results without a submission are skipped
+                        { "id": 2, "completionDate": "2025-04-02T12:00:00+02:00", "score": 90.0 },
+                    ],
+                }],
+            },
+        });
+        let (participation_id, results) =
+            Adapter::parse_exercise_details(&details.to_string(), ParticipationType::Graded).unwrap();
+        assert_eq!(participation_id, 42);
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].build_failed);
+        assert_eq!(results[0].commit_hash, None);
+        assert_eq!(results[0].score, Some(80.0));
+    }
+
+    #[test]
+    fn score_is_computed_from_points() {
+        let points_only = json!({ "achievedPoints": 3.0, "maxPoints": 4.0 });
+        assert_eq!(Adapter::result_score(&points_only, None), Some(75.0));
+        // the max points of the exercise are used if the result doesn't have its own
+        let achieved_only = json!({ "achievedPoints": 5.0 });
+        assert_eq!(Adapter::result_score(&achieved_only, Some(10.0)), Some(50.0));
+        assert_eq!(Adapter::result_score(&achieved_only, None), None);
+        assert_eq!(Adapter::result_score(&achieved_only, Some(0.0)), None);
+        // an explicit score takes precedence
+        let both = json!({ "score": 20.0, "achievedPoints": 5.0, "maxPoints": 10.0 });
+        assert_eq!(Adapter::result_score(&both, None), Some(20.0));
+    }
+
+    #[test]
+    fn task_with_full_points_and_no_score_is_completed() {
+        let raw_task = json!({
+            "id": 7,
+            "title": "Sorting",
+            "maxPoints": 8.0,
+            "studentParticipations": [{
+                "id": 70,
+                "testRun": false,
+                "results": [
+                    { "id": 1, "completionDate": "2025-04-01T12:00:00+02:00", "achievedPoints": 4.0 },
+                    { "id": 2, "completionDate": "2025-04-02T12:00:00+02:00", "achievedPoints": 8.0 },
+                ],
+            }],
+        });
+        let task = Adapter::parse_task(&raw_task).unwrap();
+        assert!(task.completed);
+        assert_eq!(task.latest_score, Some(100.0));
+        assert_eq!(task.participation_id, Some(70));
+    }
+
+    #[test]
+    fn task_with_partial_points_is_not_completed() {
+        let raw_task = json!({
+            "id": 8,
+            "title": "Graphs",
+            "maxPoints": 10.0,
+            "studentParticipations": [{
+                "id": 80,
+                "results": [{ "id": 3, "completionDate": "2025-04-01T12:00:00+02:00", "achievedPoints": 6.0 }],
+            }],
+        });
+        let task = Adapter::parse_task(&raw_task).unwrap();
+        assert!(!task.completed);
+        assert_eq!(task.latest_score, Some(60.0));
+    }
+
+    fn build_state(participation: Value) -> BuildState {
+        let details = json!({ "exercise": { "studentParticipations": [participation] } });
+        Adapter::parse_build_state(&details.to_string(), ParticipationType::Graded).unwrap()
+    }
+
+    #[test]
+    fn build_is_pending_until_every_submission_has_a_finished_result() {
+        let finished = json!({ "id": 1, "completionDate": "2025-04-01T12:00:00+02:00", "submission": { "id": 10 } });
+        let idle = build_state(json!({ "id": 5, "results": [finished], "submissions": [{ "id": 10 }] }));
+        assert_eq!(idle.participation_id, 5);
+        assert_eq!(idle.latest_result, Some(1));
+        assert!(!idle.pending);
+
+        let submissions = json!([{ "id": 10 }, { "id": 11 }]);
+        let queued = build_state(json!({ "id": 5, "results": [finished], "submissions": submissions }));
+        assert!(queued.pending);
+
+        let running = build_state(json!({ "id": 5, "results": [finished, { "id": 2, "completionDate": null }] }));
+        assert_eq!(running.latest_result, Some(2));
+        assert!(running.pending);
+    }
+
+    fn build_failure(lines: &[&str]) -> TestOutcome {
+        let time = DateTime::parse_from_rfc3339("2025-04-01T12:00:00+02:00").unwrap();
+        let logs = lines.iter().map(|line| LogStatement { time, log: line.to_string() }).collect();
+        TestOutcome::BuildFailure(logs)
+    }
+
+    #[test]
+    fn runner_timeout_is_infrastructure_failure() {
+        let outcome = build_failure(&["ERROR: Job failed: execution took longer than 10m0s seconds"]);
+        assert!(outcome.is_infrastructure_failure());
+    }
+
+    #[test]
+    fn compiler_errors_are_not_infrastructure_failures() {
+        for line in [
+            "src/Main.java:12: error: ';' expected",
+            "main.c:3:5: error: unknown type name 'strin'",
+            "error[E0425]: cannot find value `x` in this scope",
+            "[ERROR] COMPILATION ERROR :",
+        ] {
+            let outcome = build_failure(&[line, "the build timed out"]);
+            assert!(!outcome.is_infrastructure_failure(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn solution_repository_without_user_is_kept() {
+        let uri = "https://artemis.example.com/git/SORT/sort-solution.git";
+        let details = json!({ "exercise": { "solutionParticipation": { "repositoryUri": uri } } });
+        match Adapter::parse_example_solution(&details.to_string(), CloneProtocol::Ssh).unwrap() {
+            Some(ExampleSolution::Repository(url)) => assert_eq!(url, uri),
+            other => panic!("expected the solution repository, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn https_uris_are_cloned_over_https_unless_ssh_is_chosen() {
+        let uri = "https://student@artemis.example.com/git/SORT/sort-student.git";
+        assert_eq!(
+            Adapter::clone_uri(uri, CloneProtocol::Https),
+            "https://artemis.example.com/git/SORT/sort-student.git"
+        );
+        assert_eq!(
+            Adapter::clone_uri(uri, CloneProtocol::Ssh),
+            "ssh://git@artemis.example.com/git/SORT/sort-student.git"
+        );
+        let ssh = "ssh://git@artemis.example.com:7921/sort/sort-student.git";
+        assert_eq!(Adapter::clone_uri(ssh, CloneProtocol::Https), ssh);
+    }
+
+    #[test]
+    fn practice_participation_is_not_the_task_participation() {
+        let raw_task = json!({
+            "id": 9,
+            "title": "Trees",
+            "studentParticipations": [{ "id": 90, "testRun": true, "results": [] }],
+        });
+        let task = Adapter::parse_task(&raw_task).unwrap();
+        assert!(task.is_active);
+        assert_eq!(task.participation_id, None);
     }
 }
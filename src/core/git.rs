@@ -15,24 +15,218 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use anyhow::Result;
-use git2::{Cred, FetchOptions, PushOptions, RemoteCallbacks, Repository, Signature, build::RepoBuilder};
+use anyhow::{Result, anyhow, bail};
+use git2::{
+    AnnotatedCommit, Cred, DiffFormat, DiffOptions, ErrorCode, FetchOptions, Oid, PushOptions, Remote, RemoteCallbacks,
+    Repository, ResetType, Signature,
+    build::{CheckoutBuilder, RepoBuilder},
+};
 use log::{info, trace};
-use std::{env, path::Path};
+use std::{
+    cell::RefCell,
+    env,
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+    time::Duration,
+};
+
+use super::{
+    adapter::ParticipationType,
+    metadata::{CloneRegistry, METADATA_DIR, SubmitLock, TaskIndexEntry, TaskMetadata},
+};
+
+/// the remote start-task clones from and submit pushes to unless told otherwise
+pub const DEFAULT_REMOTE: &str = "origin";
+
+/// message of the commits created by start-task and by submit without a message
+const DEFAULT_COMMIT_MESSAGE: &str = "automated commit...";
+
+/// what happened when pulling the remote branch
+#[derive(Clone, Debug)]
+pub enum PullOutcome {
+    UpToDate,
+    FastForwarded,
+    /// both branches have commits the other one is missing
+    Diverged { local: Vec<String>, remote: Vec<String> },
+    /// the number of local commits replayed on top of the remote branch
+    Rebased(usize),
+}
+
+/// name and email used as author and committer of submissions
+#[derive(Clone, Debug)]
+pub struct Identity {
+    pub(crate) name: String,
+    pub(crate) email: String,
+}
+
+impl FromStr for Identity {
+    type Err = anyhow::Error;
+
+    /// parses an author in the form git uses, `Name <email>`
+    fn from_str(s: &str) -> Result<Self> {
+        let parsed = s.trim().strip_suffix('>').and_then(|rest| rest.split_once('<'));
+        match parsed {
+            Some((name, email)) if !name.trim().is_empty() && !email.trim().is_empty() => Ok(Identity {
+                name: name.trim().to_string(),
+                email: email.trim().to_string(),
+            }),
+            _ => bail!("'{}' is not a valid author, expected \"Name <email>\"", s),
+        }
+    }
+}
+
+impl Display for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// headers that authenticate https requests to the git server of artemis, e.g. the session cookie.
+/// they are only sent to remotes on the host of artemis, never to other remotes such as a backup on github
+#[derive(Clone, Debug, Default)]
+pub struct HttpAuth {
+    /// host and port of artemis
+    host: Option<(String, u16)>,
+    headers: Vec<String>,
+}
+
+impl HttpAuth {
+    /// `headers` are sent to remotes on the same host as `base_url`
+    pub fn new(base_url: &str, headers: Vec<String>) -> Self {
+        Self {
+            host: Self::host_of(base_url),
+            headers,
+        }
+    }
+
+    fn host_of(url: &str) -> Option<(String, u16)> {
+        let url = reqwest::Url::parse(url).ok()?;
+        Some((url.host_str()?.to_lowercase(), url.port_or_known_default()?))
+    }
+
+    /// the headers to send to the remote at `url`, none if it isn't artemis or isn't reached over http(s)
+    fn headers_for(&self, url: &str) -> Vec<&str> {
+        let is_http = url.starts_with("https://") || url.starts_with("http://");
+        match &self.host {
+            Some(host) if is_http && Self::host_of(url).as_ref() == Some(host) => {
+                self.headers.iter().map(String::as_str).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
 
 pub struct ArtemisRepo {
     repo: Repository,
+    metadata: Option<TaskMetadata>,
+    http_auth: HttpAuth,
 }
 
 impl ArtemisRepo {
-    pub fn create(url: &str, task_id: u64) -> Result<Self> {
-        let mut path = env::current_dir().expect("can't access current directory");
-        path.push(format!("artemis-task-nr-{}", task_id).as_str());
+    /// sets the connect and read/write timeout of all git network operations, e.g. clone and push
+    pub fn set_network_timeout(timeout: Duration) -> Result<()> {
+        let millis = i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX);
+        // SAFETY: libgit2 options are global, this is called before any git operation is started
+        unsafe {
+            git2::opts::set_server_connect_timeout_in_milliseconds(millis)?;
+            git2::opts::set_server_timeout_in_milliseconds(millis)?;
+        }
+        Ok(())
+    }
+
+    /// the directory start-task clones into without a template, `artemis-task-nr-{id}` in the current directory
+    pub fn default_dir(task_id: u64, participation_type: ParticipationType) -> Result<PathBuf> {
+        let mut path = env::current_dir()?;
+        match participation_type {
+            ParticipationType::Graded => path.push(format!("artemis-task-nr-{}", task_id).as_str()),
+            ParticipationType::Practice => path.push(format!("artemis-task-nr-{}-practice", task_id).as_str()),
+        }
+        Ok(path)
+    }
+
+    /// the directory the example solution of a task is cloned into, next to the task repository
+    pub fn solution_dir(task_id: u64) -> Result<PathBuf> {
+        Ok(env::current_dir()?.join(format!("artemis-task-nr-{}-solution", task_id)))
+    }
+
+    /// clones the solution repository at `url` into `path`, or updates it if it was cloned before. the files
+    /// are made read-only so the solution isn't edited by accident
+    pub fn clone_solution(url: &str, path: &Path, http_auth: &HttpAuth) -> Result<()> {
+        let git_url_rel = Self::git_url(url);
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|_url, username_from_url, _allowed_types| Cred::ssh_key_from_agent(username_from_url.unwrap_or("git")));
+        let headers = http_auth.headers_for(&git_url_rel);
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        fetch_options.custom_headers(&headers);
+
+        if path.join(".git").exists() {
+            let repo = Repository::open(path)?;
+            trace!("updating the solution in {}...", path.display());
+            repo.find_remote("origin")?.fetch(&[] as &[&str], Some(&mut fetch_options), None)?;
+            let latest = repo.find_reference("FETCH_HEAD")?.peel_to_commit()?;
+            set_read_only(path, false)?;
+            repo.reset(latest.as_object(), ResetType::Hard, None)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            info!("cloning the solution: {} into {} ...", git_url_rel, path.display());
+            RepoBuilder::new().fetch_options(fetch_options).clone(&git_url_rel, path)?;
+        }
+        set_read_only(path, true)
+    }
+
+    /// https urls are cloned as they are and authenticated by the headers, e.g. with the session cookie.
+    /// `ssh://git@host/path` becomes the scp-like `git@host:path`
+    fn git_url(url: &str) -> String {
+        if url.starts_with("https://") || url.starts_with("http://") {
+            return url.to_string();
+        }
+        let git_url_abs = url.split_once("//").map_or(url, |(_, rest)| rest);
+        git_url_abs.replacen("/", ":", 1).replace("\"", "")
+    }
+
+    /// expands the placeholders of a `clone_dir_template`, practice participations get a `-practice` suffix
+    pub fn dir_from_template(
+        template: &str,
+        task: &TaskIndexEntry,
+        participation_type: ParticipationType,
+    ) -> Result<PathBuf> {
+        let mut expanded = template
+            .replace("{course_id}", &task.course_id.to_string())
+            .replace("{course_title}", &sanitize_path_segment(&task.course_title))
+            .replace("{task_id}", &task.task_id.to_string())
+            .replace("{task_title}", &sanitize_path_segment(&task.task_title));
+        if participation_type == ParticipationType::Practice {
+            expanded.push_str("-practice");
+        }
+
+        let path = match expanded.strip_prefix("~/") {
+            Some(rest) => env::home_dir().ok_or(anyhow!("can't get HOME directory"))?.join(rest),
+            None => PathBuf::from(expanded),
+        };
+        Ok(if path.is_absolute() { path } else { env::current_dir()?.join(path) })
+    }
 
-        let git_url_abs = url.split_once("//").unwrap().1;
-        let git_url_rel = git_url_abs.replacen("/", ":", 1).replace("\"", "");
+    pub fn create(
+        url: &str,
+        task_id: u64,
+        participation_type: ParticipationType,
+        template: Option<&str>,
+        path: &Path,
+        http_auth: HttpAuth,
+    ) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
 
-        info!("start cloning: {} into {} ...", git_url_rel, path.to_str().unwrap());
+        let git_url_rel = Self::git_url(url);
+        info!("start cloning: {} into {} ...", git_url_rel, path.display());
 
         let mut callbacks = RemoteCallbacks::new();
 
@@ -43,31 +237,221 @@ impl ArtemisRepo {
             Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
         });
 
+        let headers = http_auth.headers_for(&git_url_rel);
         let mut fetch_options = FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
+        fetch_options.custom_headers(&headers);
 
         let mut builder = RepoBuilder::new();
         builder.fetch_options(fetch_options);
 
-        let repo = builder.clone(&git_url_rel, &path)?;
-        Ok(Self { repo })
+        let repo = builder.clone(&git_url_rel, path)?;
+
+        let metadata = TaskMetadata {
+            task_id,
+            branch: Self::detect_branch(&repo)?,
+            participation_type,
+            template: template.map(str::to_string),
+        };
+        metadata.save(path)?;
+        trace!("stored task metadata: {:?}", metadata);
+        CloneRegistry::register(task_id, path, template.is_some())?;
+
+        let s = Self {
+            repo,
+            metadata: Some(metadata),
+            http_auth,
+        };
+        s.exclude_metadata()?;
+        Ok(s)
+    }
+
+    /// opens the repository without reading or migrating its task metadata, for callers that know the task
+    pub fn open_unchecked(path: &Path) -> Result<Self> {
+        let repo = Repository::open(path)
+            .map_err(|e| anyhow!("{} is not a git repository: {}", path.display(), e.message()))?;
+        Ok(Self {
+            repo,
+            metadata: None,
+            http_auth: HttpAuth::default(),
+        })
     }
 
     pub fn open<T>(path: T) -> Result<Self>
     where
         T: AsRef<Path>,
     {
-        let repo = Repository::open(path)?;
-        Ok(Self { repo })
+        let path = path.as_ref();
+        let repo = Repository::open(path)
+            .map_err(|e| anyhow!("{} is not a git repository: {}", path.display(), e.message()))?;
+        let workdir = repo.workdir().ok_or(anyhow!("repository has no working directory"))?;
+        let mut metadata = TaskMetadata::load(workdir)?;
+
+        // repositories cloned before the metadata existed get it created on first use
+        if metadata.is_none()
+            && let Ok(task_id) = Self::task_id_from_dir(workdir)
+        {
+            let migrated = TaskMetadata {
+                task_id,
+                branch: Self::detect_branch(&repo)?,
+                participation_type: Self::participation_type_from_dir(workdir),
+                template: None,
+            };
+            migrated.save(workdir)?;
+            CloneRegistry::register(task_id, workdir, false)?;
+            info!("created missing task metadata: {:?}", migrated);
+            metadata = Some(migrated);
+        }
+
+        let s = Self {
+            repo,
+            metadata,
+            http_auth: HttpAuth::default(),
+        };
+        if s.metadata.is_some() {
+            s.exclude_metadata()?;
+        }
+        Ok(s)
+    }
+
+    /// true if the `.gitattributes` route any files through the Git LFS filter
+    pub fn uses_lfs(&self) -> bool {
+        self.repo
+            .workdir()
+            .and_then(|workdir| fs::read_to_string(workdir.join(".gitattributes")).ok())
+            .is_some_and(|attributes| attributes.lines().any(|line| line.contains("filter=lfs")))
+    }
+
+    /// replaces the LFS pointer files with their content, libgit2 can't do this so it needs the git-lfs binary
+    pub fn fetch_lfs(&self) -> Result<()> {
+        let workdir = self.repo.workdir().ok_or(anyhow!("repository has no working directory"))?;
+        trace!("running git lfs pull in {}...", workdir.display());
+        let output = Command::new("git")
+            .args(["lfs", "pull"])
+            .current_dir(workdir)
+            .output()
+            .map_err(|e| anyhow!("can't run git: {}", e))?;
+
+        if !output.status.success() {
+            bail!("git lfs pull failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        info!("fetched LFS files");
+        Ok(())
+    }
+
+    pub fn task_id(&self) -> Result<u64> {
+        match &self.metadata {
+            Some(metadata) => Ok(metadata.task_id),
+            None => Self::task_id_from_dir(self.repo.workdir().ok_or(anyhow!("repository has no working directory"))?),
+        }
+    }
+
+    /// the auxiliary repository that was cloned instead of the student repository, if any
+    pub fn template(&self) -> Option<&str> {
+        self.metadata.as_ref().and_then(|metadata| metadata.template.as_deref())
+    }
+
+    /// the participation submissions of this repository count towards
+    pub fn participation_type(&self) -> ParticipationType {
+        self.metadata.as_ref().map(|metadata| metadata.participation_type).unwrap_or_default()
+    }
+
+    /// the branch submissions are pushed to
+    pub fn branch(&self) -> Result<String> {
+        match &self.metadata {
+            Some(metadata) => Ok(metadata.branch.clone()),
+            None => Self::detect_branch(&self.repo),
+        }
+    }
+
+    /// locks the repository against concurrent submits until the returned guard is dropped
+    pub fn lock_submit(&self) -> Result<SubmitLock> {
+        let workdir = self.repo.workdir().ok_or(anyhow!("repository has no working directory"))?;
+        SubmitLock::acquire(workdir)
+    }
+
+    /// the local branch HEAD points to, an error if HEAD is detached
+    pub fn current_branch(&self) -> Result<String> {
+        if self.repo.head_detached()? {
+            bail!("HEAD is detached, check out a branch with 'git switch [BRANCH]' before submitting");
+        }
+        let head = self.repo.head()?;
+        head.shorthand()
+            .map(str::to_string)
+            .ok_or(anyhow!("the name of the current branch is not valid utf8"))
+    }
+
+    /// reads the task id from the name of the directory created by `create`
+    fn task_id_from_dir(workdir: &Path) -> Result<u64> {
+        workdir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("artemis-task-nr-"))
+            .and_then(|id| id.trim_end_matches("-practice").parse().ok())
+            .ok_or(anyhow!("{} is not a directory created by start-task", workdir.display()))
+    }
+
+    fn participation_type_from_dir(workdir: &Path) -> ParticipationType {
+        match workdir.file_name().and_then(|name| name.to_str()) {
+            Some(name) if name.ends_with("-practice") => ParticipationType::Practice,
+            _ => ParticipationType::Graded,
+        }
     }
 
-    pub fn commit_and_push(&self) -> Result<()> {
-        self.commit()?;
-        self.push()?;
+    /// determines the default branch, preferring the remote HEAD over the local one
+    fn detect_branch(repo: &Repository) -> Result<String> {
+        if let Ok(remote_head) = repo.find_reference("refs/remotes/origin/HEAD")
+            && let Some(target) = remote_head.symbolic_target()
+            && let Some(branch) = target.strip_prefix("refs/remotes/origin/")
+        {
+            return Ok(branch.to_string());
+        }
+        let head = repo.head()?;
+        head.shorthand().map(str::to_string).ok_or(anyhow!("can't determine the branch of HEAD"))
+    }
+
+    /// keeps the metadata directory out of the submitted commits
+    fn exclude_metadata(&self) -> Result<()> {
+        let exclude_path = self.repo.path().join("info").join("exclude");
+        let pattern = format!("/{}/", METADATA_DIR);
+
+        let existing = fs::read_to_string(&exclude_path).unwrap_or_default();
+        if existing.lines().any(|line| line.trim() == pattern) {
+            return Ok(());
+        }
+
+        if let Some(parent) = exclude_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut exclude = OpenOptions::new().create(true).append(true).open(&exclude_path)?;
+        if !existing.is_empty() && !existing.ends_with('\n') {
+            writeln!(exclude)?;
+        }
+        writeln!(exclude, "{}", pattern)?;
         Ok(())
     }
 
-    pub fn commit(&self) -> Result<()> {
+    /// the identity from the git config, None if user.name or user.email is not set
+    pub fn git_identity() -> Option<Identity> {
+        let config = git2::Config::open_default().ok()?;
+        Some(Identity {
+            name: config.get_string("user.name").ok()?,
+            email: config.get_string("user.email").ok()?,
+        })
+    }
+
+    /// commits all changes with `message`, or a generic message if there is none, and pushes them to `remote`.
+    /// returns the id of the new commit
+    pub fn commit_and_push(&self, identity: &Identity, message: Option<&str>, remote: &str) -> Result<Oid> {
+        // fail before committing, a commit on a detached HEAD or for a missing remote couldn't be pushed anyway
+        self.current_branch()?;
+        self.find_remote(remote)?;
+        let commit_id = self.commit(identity, message.unwrap_or(DEFAULT_COMMIT_MESSAGE))?;
+        self.push(remote)?;
+        Ok(commit_id)
+    }
+
+    pub fn commit(&self, identity: &Identity, message: &str) -> Result<Oid> {
         let mut index = self.repo.index()?;
 
         trace!("indexing files...");
@@ -82,30 +466,69 @@ impl ArtemisRepo {
         let head = self.repo.head()?;
         let parent = head.peel_to_commit()?;
 
-        trace!("loading name and email from config...");
-        let config = git2::Config::open_default()?;
-        let name = config
-            .get_string("user.name")
-            .expect("no username for git configured. Run git config --global user.name 'YourName'");
-        let email = config
-            .get_string("user.email")
-            .expect("no email for git configured. Run git config --global user.email 'YourEmail'");
-
-        let signature = Signature::now(&name, &email)?;
+        let signature = Signature::now(&identity.name, &identity.email)?;
 
         trace!("running commit...");
         let commit_id = self
             .repo
-            .commit(Some("HEAD"), &signature, &signature, "automated commit...", &tree, &[&parent])?;
+            .commit(Some("HEAD"), &signature, &signature, message, &tree, &[&parent])?;
         info!("successfully commited {}", commit_id);
 
-        Ok(())
+        Ok(commit_id)
     }
 
-    pub fn push(&self) -> Result<()> {
+    /// sets the headers sent to https remotes on the host of artemis, they are ignored for all other remotes
+    pub fn set_http_auth(&mut self, http_auth: HttpAuth) {
+        self.http_auth = http_auth;
+    }
+
+    /// whether `remote` is reached over http(s) instead of ssh
+    pub fn uses_http(&self, remote: &str) -> bool {
+        self.repo
+            .find_remote(remote)
+            .ok()
+            .and_then(|remote| remote.url().map(|url| url.starts_with("https://") || url.starts_with("http://")))
+            .unwrap_or(false)
+    }
+
+    /// the remote called `name`, the error lists the remotes that exist instead
+    fn find_remote(&self, name: &str) -> Result<Remote<'_>> {
+        self.repo.find_remote(name).map_err(|_| {
+            let remotes = self.repo.remotes().map(|remotes| remotes.iter().flatten().map(str::to_string).collect());
+            let remotes: Vec<String> = remotes.unwrap_or_default();
+            anyhow!("the repository has no remote '{}', available are: {}", name, remotes.join(", "))
+        })
+    }
+
+    pub fn push(&self, remote: &str) -> Result<()> {
+        self.push_refspec(remote, &self.submit_refspec()?)
+    }
+
+    /// pushes the checked out branch to the default branch, artemis only builds its default branch
+    fn submit_refspec(&self) -> Result<String> {
+        Ok(format!("refs/heads/{}:refs/heads/{}", self.current_branch()?, self.branch()?))
+    }
+
+    /// creates the lightweight tag `name` on `commit`, `submission-N` for the N-th submission if there is no name,
+    /// and pushes it to `remote`. returns the name of the tag
+    pub fn tag_submission(&self, commit: &str, name: Option<&str>, remote: &str) -> Result<String> {
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => format!("submission-{}", self.submission_count()?),
+        };
+        let target = self.repo.find_object(Oid::from_str(commit)?, None)?;
+        self.repo
+            .tag_lightweight(&name, &target, false)
+            .map_err(|e| anyhow!("can't create the tag {}: {}", name, e.message()))?;
+        self.push_refspec(remote, &format!("refs/tags/{}:refs/tags/{}", name, name))?;
+        Ok(name)
+    }
+
+    fn push_refspec(&self, remote: &str, refspec: &str) -> Result<()> {
         trace!("trying to find remote...");
-        let mut remote = self.repo.find_remote("origin")?;
+        let mut remote = self.find_remote(remote)?;
 
+        let rejections = RefCell::new(Vec::new());
         let mut callbacks = RemoteCallbacks::new();
         trace!("adding callback...");
         callbacks.credentials(|_url, username_from_url, _allowed_types| Cred::ssh_key_from_agent(username_from_url.unwrap_or("git")));
@@ -114,15 +537,351 @@ impl ArtemisRepo {
             true
         });
 
+        // the server reports per ref whether it accepted the update, a rejection isn't an error of push itself
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(message) = status {
+                rejections.borrow_mut().push(format!("{} ({})", refname, message));
+            }
+            Ok(())
+        });
+
         // Configure push options
+        let url = remote.url().unwrap_or_default().to_string();
+        let headers = self.http_auth.headers_for(&url);
         let mut push_options = PushOptions::new();
         push_options.remote_callbacks(callbacks);
+        push_options.custom_headers(&headers);
 
-        trace!("pushing...");
-        remote.push(&["refs/heads/main:refs/heads/main"], Some(&mut push_options))?;
+        trace!("pushing {}...", refspec);
+        remote.push(&[refspec], Some(&mut push_options))?;
 
+        let rejections = rejections.borrow();
+        if !rejections.is_empty() {
+            bail!(
+                "the remote rejected the push of {}, your submission didn't arrive. \
+                 if the remote has new commits run 'artemis-cli pull' first",
+                rejections.join(", ")
+            );
+        }
         info!("successfully pushed to remote");
 
         Ok(())
     }
+
+    /// fetches the default branch from `remote` and fast-forwards the local branch to it.
+    /// If the branches diverged, the local commits are replayed on top of the remote ones when `rebase` is set.
+    pub fn pull(&self, remote: &str, rebase: bool, identity: &Identity) -> Result<PullOutcome> {
+        let branch = self.branch()?;
+        // merging works on HEAD, pulling the default branch into another one would leave HEAD on the wrong tree
+        let current = self.current_branch()?;
+        if current != branch {
+            bail!(
+                "you are on branch '{}', switch to '{}' with 'git switch {}' before pulling",
+                current,
+                branch,
+                branch
+            );
+        }
+        let tracking_ref = format!("refs/remotes/{}/{}", remote, branch);
+
+        let mut remote = self.find_remote(remote)?;
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|_url, username_from_url, _allowed_types| Cred::ssh_key_from_agent(username_from_url.unwrap_or("git")));
+        let url = remote.url().unwrap_or_default().to_string();
+        let headers = self.http_auth.headers_for(&url);
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        fetch_options.custom_headers(&headers);
+
+        trace!("fetching {}...", branch);
+        remote.fetch(&[format!("+refs/heads/{}:{}", branch, tracking_ref)], Some(&mut fetch_options), None)?;
+
+        let upstream = self.repo.reference_to_annotated_commit(&self.repo.find_reference(&tracking_ref)?)?;
+        let (analysis, _) = self.repo.merge_analysis(&[&upstream])?;
+
+        if analysis.is_up_to_date() {
+            return Ok(PullOutcome::UpToDate);
+        }
+
+        if analysis.is_fast_forward() {
+            trace!("fast-forwarding to {}...", upstream.id());
+            // update the working tree first so local changes that would be overwritten abort the pull
+            let target = self.repo.find_object(upstream.id(), None)?;
+            self.repo.checkout_tree(&target, Some(CheckoutBuilder::new().safe()))?;
+            self.repo
+                .find_reference(&format!("refs/heads/{}", branch))?
+                .set_target(upstream.id(), "artemis-cli: fast-forward")?;
+            return Ok(PullOutcome::FastForwarded);
+        }
+
+        if !rebase {
+            let head = self.repo.head()?.peel_to_commit()?.id();
+            return Ok(PullOutcome::Diverged {
+                local: self.commits_between(upstream.id(), head)?,
+                remote: self.commits_between(head, upstream.id())?,
+            });
+        }
+
+        self.rebase_onto(&upstream, identity)
+    }
+
+    /// replays the local commits on top of `upstream`, aborting the rebase if a commit doesn't apply cleanly
+    fn rebase_onto(&self, upstream: &AnnotatedCommit, identity: &Identity) -> Result<PullOutcome> {
+        let local = self.repo.reference_to_annotated_commit(&self.repo.head()?)?;
+        let signature = Signature::now(&identity.name, &identity.email)?;
+        let mut rebase = self.repo.rebase(Some(&local), Some(upstream), None, None)?;
+
+        let mut replayed = 0;
+        while let Some(operation) = rebase.next() {
+            let operation = operation?;
+            trace!("replaying {}...", operation.id());
+
+            let index = self.repo.index()?;
+            if index.has_conflicts() {
+                let conflicts = index
+                    .conflicts()?
+                    .filter_map(|conflict| conflict.ok())
+                    .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+                    .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+                    .collect::<Vec<_>>();
+                rebase.abort()?;
+                bail!(
+                    "can't replay {} because of conflicts in:\n  {}\nthe rebase was aborted and the repository restored",
+                    operation.id(),
+                    conflicts.join("\n  ")
+                );
+            }
+
+            match rebase.commit(None, &signature, None) {
+                Ok(_) => replayed += 1,
+                // the remote already contains the changes of this commit
+                Err(e) if e.code() == ErrorCode::Applied => trace!("{} is already applied", operation.id()),
+                Err(e) => {
+                    rebase.abort()?;
+                    return Err(e.into());
+                }
+            }
+        }
+        rebase.finish(Some(&signature))?;
+
+        Ok(PullOutcome::Rebased(replayed))
+    }
+
+    /// the changes a commit would contain as a patch, i.e. the working tree including untracked files against HEAD
+    pub fn uncommitted_diff(&self) -> Result<String> {
+        self.diff_since(self.repo.head()?.peel_to_commit()?.id())
+    }
+
+    /// the working tree including untracked files against `commit` as a patch
+    pub fn diff_since(&self, commit: Oid) -> Result<String> {
+        let tree = self.repo.find_commit(commit)?.tree()?;
+        let mut options = DiffOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true).show_untracked_content(true);
+        let diff = self.repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))?;
+
+        let mut patch = String::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin());
+            }
+            patch.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })?;
+        Ok(patch)
+    }
+
+    /// resolves a revision like a (short) sha or `HEAD~2` to a commit
+    pub fn resolve_commit(&self, revision: &str) -> Result<Oid> {
+        let object = self
+            .repo
+            .revparse_single(revision)
+            .map_err(|e| anyhow!("unknown commit '{}': {}", revision, e.message()))?;
+        Ok(object.peel_to_commit()?.id())
+    }
+
+    /// the commit of the `n`-th submission, counting commits from the root where 0 is the initial template
+    pub fn submission_commit(&self, n: usize) -> Result<Oid> {
+        let mut commits = self.first_parent_commits()?;
+        commits.reverse();
+        commits.get(n).copied().ok_or(anyhow!(
+            "there is no submission {}, the repository has {} submissions",
+            n,
+            commits.len().saturating_sub(1)
+        ))
+    }
+
+    /// the number of submissions up to HEAD, counted like `submission_commit` does
+    fn submission_count(&self) -> Result<usize> {
+        Ok(self.first_parent_commits()?.len().saturating_sub(1))
+    }
+
+    /// the commits from HEAD back to the root, following only first parents
+    fn first_parent_commits(&self) -> Result<Vec<Oid>> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.simplify_first_parent()?;
+        Ok(revwalk.collect::<Result<Vec<Oid>, _>>()?)
+    }
+
+    /// lists the commits reachable from `to` but not from `from` as "<short id> <summary>"
+    fn commits_between(&self, from: Oid, to: Oid) -> Result<Vec<String>> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(to)?;
+        revwalk.hide(from)?;
+
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            let short_id = commit.as_object().short_id()?;
+            commits.push(format!("{} {}", short_id.as_str().unwrap_or_default(), commit.summary().unwrap_or_default()));
+        }
+        Ok(commits)
+    }
+}
+
+/// changes the permissions of the files of the working tree in `dir`, the git directory is left writable
+fn set_read_only(dir: &Path, read_only: bool) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            set_read_only(&path, read_only)?;
+        } else {
+            let mut permissions = fs::metadata(&path)?.permissions();
+            permissions.set_readonly(read_only);
+            fs::set_permissions(&path, permissions)?;
+        }
+    }
+    Ok(())
+}
+
+/// maximum length in bytes of a sanitized title, leaves room for the `-practice` and auxiliary repository suffixes
+/// within the 255 byte file name limit of most file systems
+const MAX_SEGMENT_LEN: usize = 200;
+
+/// replaces characters that aren't allowed or are awkward in file names, titles can contain anything.
+/// other non-ascii characters such as umlauts are kept, long titles are cut at a character boundary
+pub fn sanitize_path_segment(title: &str) -> String {
+    let sanitized: String = title
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_whitespace() => '-',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .scan(0, |len, c| {
+            *len += c.len_utf8();
+            (*len <= MAX_SEGMENT_LEN).then_some(c)
+        })
+        .collect();
+    let sanitized = sanitized.trim_matches('.');
+    if sanitized.is_empty() { "_".to_string() } else { sanitized.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArtemisRepo, HttpAuth, Identity, MAX_SEGMENT_LEN, sanitize_path_segment};
+    use crate::core::metadata::TaskMetadata;
+    use git2::{Repository, Signature};
+    use std::{fs, path::PathBuf};
+
+    /// a repository with a single commit on `main` in a fresh temporary directory
+    fn temp_repo(name: &str) -> (PathBuf, ArtemisRepo) {
+        let dir = std::env::temp_dir().join(format!("artemis-cli-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[]).unwrap();
+        drop(tree);
+        let metadata = TaskMetadata {
+            task_id: 1,
+            branch: "main".to_string(),
+            participation_type: Default::default(),
+            template: None,
+        };
+        let repo = ArtemisRepo {
+            repo,
+            metadata: Some(metadata),
+            http_auth: HttpAuth::default(),
+        };
+        (dir, repo)
+    }
+
+    #[test]
+    fn detached_head_is_refused() {
+        let (dir, repo) = temp_repo("detached");
+        let head = repo.repo.head().unwrap().target().unwrap();
+        repo.repo.set_head_detached(head).unwrap();
+
+        let err = repo.submit_refspec().unwrap_err();
+        assert!(err.to_string().contains("HEAD is detached"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn other_branch_is_pushed_to_default_branch() {
+        let (dir, repo) = temp_repo("branch");
+        let head = repo.repo.head().unwrap().peel_to_commit().unwrap();
+        repo.repo.branch("feature", &head, false).unwrap();
+        repo.repo.set_head("refs/heads/feature").unwrap();
+
+        assert_eq!(repo.current_branch().unwrap(), "feature");
+        assert_eq!(repo.submit_refspec().unwrap(), "refs/heads/feature:refs/heads/main");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn pull_on_other_branch_is_refused() {
+        let (dir, repo) = temp_repo("pull");
+        let head = repo.repo.head().unwrap().peel_to_commit().unwrap();
+        repo.repo.branch("feature", &head, false).unwrap();
+        repo.repo.set_head("refs/heads/feature").unwrap();
+
+        let identity = Identity {
+            name: "Test".to_string(),
+            email: "test@example.com".to_string(),
+        };
+        let err = repo.pull("origin", false, &identity).unwrap_err();
+        assert!(err.to_string().contains("you are on branch 'feature'"), "{}", err);
+        // nothing was touched, HEAD stays on the feature branch
+        assert_eq!(repo.current_branch().unwrap(), "feature");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn default_branch_is_pushed_to_itself() {
+        let (dir, repo) = temp_repo("main");
+        assert_eq!(repo.submit_refspec().unwrap(), "refs/heads/main:refs/heads/main");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn sanitize_replaces_separators_and_keeps_umlauts() {
+        assert_eq!(sanitize_path_segment("Aufgabe 1/2: Bäume"), "Aufgabe-1_2_-Bäume");
+    }
+
+    #[test]
+    fn sanitize_truncates_long_titles_at_char_boundary() {
+        let sanitized = sanitize_path_segment(&"ä".repeat(MAX_SEGMENT_LEN));
+        assert_eq!(sanitized.len(), MAX_SEGMENT_LEN);
+        assert_eq!(sanitized, "ä".repeat(MAX_SEGMENT_LEN / 2));
+
+        // a two byte character that doesn't fit anymore is dropped instead of cut in half
+        let sanitized = sanitize_path_segment(&format!("a{}", "ä".repeat(MAX_SEGMENT_LEN)));
+        assert_eq!(sanitized.len(), MAX_SEGMENT_LEN - 1);
+        assert!(sanitized.starts_with('a'));
+    }
+
+    #[test]
+    fn sanitize_never_returns_an_empty_segment() {
+        assert_eq!(sanitize_path_segment("  ..  "), "_");
+    }
 }
@@ -0,0 +1,360 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local, TimeDelta};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use super::adapter::{Course, ParticipationType, ResultInfo};
+use crate::config::{cache_dir, data_dir};
+
+/// directory inside the working tree that holds the artemis-cli metadata of a task
+pub const METADATA_DIR: &str = ".artemis";
+const METADATA_FILE: &str = "task.toml";
+const SUBMIT_LOCK_FILE: &str = "submit.lock";
+/// a lock older than this is left over from a crashed submit and may be taken over
+const STALE_LOCK_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// information about a cloned task that is stored alongside the repository
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskMetadata {
+    pub(crate) task_id: u64,
+    /// the default branch of the remote as determined when cloning
+    pub(crate) branch: String,
+    /// the participation the repository belongs to
+    #[serde(default)]
+    pub(crate) participation_type: ParticipationType,
+    /// the auxiliary repository that was cloned instead of the student repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) template: Option<String>,
+}
+
+impl TaskMetadata {
+    fn path(workdir: &Path) -> PathBuf {
+        workdir.join(METADATA_DIR).join(METADATA_FILE)
+    }
+
+    /// loads the metadata of the repository in `workdir`, returns None for repositories cloned before
+    /// the metadata existed
+    pub fn load(workdir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(workdir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(&path).with_context(|| format!("can't read {}", path.display()))?;
+        let metadata = toml::from_str(&text).with_context(|| format!("can't parse {}", path.display()))?;
+        Ok(Some(metadata))
+    }
+
+    pub fn save(&self, workdir: &Path) -> Result<()> {
+        let path = Self::path(workdir);
+        fs::create_dir_all(workdir.join(METADATA_DIR))?;
+        fs::write(&path, toml::to_string(self)?).with_context(|| format!("can't write {}", path.display()))
+    }
+}
+
+/// a task repository cloned by start-task
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClonedTask {
+    pub(crate) task_id: u64,
+    pub(crate) path: PathBuf,
+    /// a clone of an auxiliary repository, e.g. the template, that can't be submitted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) auxiliary: bool,
+}
+
+/// all task repositories cloned by start-task, so they can be found again e.g. by clean
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CloneRegistry {
+    #[serde(default)]
+    clones: Vec<ClonedTask>,
+}
+
+impl CloneRegistry {
+    fn path() -> PathBuf {
+        data_dir().join("clones.toml")
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(&path).with_context(|| format!("can't read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("can't parse {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        fs::create_dir_all(data_dir())?;
+        fs::write(&path, toml::to_string(self)?).with_context(|| format!("can't write {}", path.display()))
+    }
+
+    /// adds the clone at `path` to the registry and saves it
+    pub fn register(task_id: u64, path: &Path, auxiliary: bool) -> Result<()> {
+        let mut registry = Self::load()?;
+        let path = path.canonicalize()?;
+        if !registry.clones.iter().any(|clone| clone.path == path) {
+            registry.clones.push(ClonedTask {
+                task_id,
+                path,
+                auxiliary,
+            });
+            registry.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn clones(&self) -> &[ClonedTask] {
+        &self.clones
+    }
+
+    /// the clones of the student repositories of the task, leaving out auxiliary repositories
+    pub fn task_clones(&self, task_id: u64) -> impl Iterator<Item = &ClonedTask> {
+        self.clones.iter().filter(move |clone| clone.task_id == task_id && !clone.auxiliary)
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.clones.retain(|clone| clone.path != path);
+    }
+}
+
+/// the course and participation a task belongs to, as seen by the last dashboard fetch
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskIndexEntry {
+    pub(crate) task_id: u64,
+    pub(crate) task_title: String,
+    pub(crate) course_id: u64,
+    pub(crate) course_title: String,
+    /// the graded participation, lets per-task commands fetch results without the exercise details
+    pub(crate) participation_id: Option<u64>,
+    /// the titles of the prerequisites that have to be completed before the task can be started
+    #[serde(default)]
+    pub(crate) locked_by: Vec<String>,
+    pub(crate) updated: DateTime<Local>,
+}
+
+/// maps task ids to their course, so commands that only get a task id don't have to fetch the dashboard
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TaskIndex {
+    #[serde(default)]
+    tasks: Vec<TaskIndexEntry>,
+}
+
+impl TaskIndex {
+    fn path() -> PathBuf {
+        cache_dir().join("tasks.toml")
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(&path).with_context(|| format!("can't read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("can't parse {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        fs::create_dir_all(cache_dir())?;
+        fs::write(&path, toml::to_string(self)?).with_context(|| format!("can't write {}", path.display()))
+    }
+
+    /// replaces the index with the tasks of `courses` and saves it
+    pub fn update(courses: &[Course]) -> Result<()> {
+        let updated = Local::now();
+        let tasks = courses
+            .iter()
+            .flat_map(|course| {
+                course.tasks.iter().map(move |task| TaskIndexEntry {
+                    task_id: task.id,
+                    task_title: task.title.clone(),
+                    course_id: course.id,
+                    course_title: course.title.clone(),
+                    participation_id: task.participation_id,
+                    locked_by: course.locked_by(task).iter().map(|prerequisite| prerequisite.title.clone()).collect(),
+                    updated,
+                })
+            })
+            .collect();
+        Self { tasks }.save()
+    }
+
+    /// the entry of `task_id` if it isn't older than `max_age`
+    pub fn lookup(task_id: u64, max_age: TimeDelta) -> Result<Option<TaskIndexEntry>> {
+        let oldest = Local::now() - max_age;
+        Ok(Self::load()?.tasks.into_iter().find(|entry| entry.task_id == task_id && entry.updated >= oldest))
+    }
+}
+
+/// a completed result with the raw response of its details, which never change once the build finished
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedResult {
+    pub(crate) result: ResultInfo,
+    pub(crate) details: String,
+}
+
+/// result details on disk keyed by participation and result id, so fetching the same result again needs no download
+pub struct ResultCache;
+
+impl ResultCache {
+    fn path(participation_id: u64, result_id: u64) -> PathBuf {
+        cache_dir().join("results").join(format!("{}-{}.json", participation_id, result_id))
+    }
+
+    /// the cached result, None if it isn't cached or the cache file can't be read
+    pub fn load(participation_id: u64, result_id: u64) -> Option<CachedResult> {
+        let path = Self::path(participation_id, result_id);
+        let text = fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&text)
+            .inspect_err(|e| debug!("ignoring unreadable cache file {}: {}", path.display(), e))
+            .ok()
+    }
+
+    pub fn save(participation_id: u64, cached: &CachedResult) -> Result<()> {
+        let path = Self::path(participation_id, cached.result.id);
+        fs::create_dir_all(cache_dir().join("results"))?;
+        fs::write(&path, serde_json::to_string(cached)?).with_context(|| format!("can't write {}", path.display()))
+    }
+}
+
+/// a submission that was pushed but whose result hasn't been seen yet
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingResult {
+    pub(crate) task_id: u64,
+    #[serde(default)]
+    pub(crate) participation_type: ParticipationType,
+    pub(crate) commit: String,
+    pub(crate) pushed_at: DateTime<Local>,
+}
+
+/// submissions awaiting their result, so an interrupted submit can be recovered by fetch or submit --resume
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PendingResults {
+    #[serde(default)]
+    pending: Vec<PendingResult>,
+}
+
+impl PendingResults {
+    fn path() -> PathBuf {
+        data_dir().join("pending.toml")
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(&path).with_context(|| format!("can't read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("can't parse {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        fs::create_dir_all(data_dir())?;
+        fs::write(&path, toml::to_string(self)?).with_context(|| format!("can't write {}", path.display()))
+    }
+
+    /// records `entry` and saves it, replacing an older submission of the same participation
+    pub fn add(entry: PendingResult) -> Result<()> {
+        let mut pending = Self::load()?;
+        pending.remove(entry.task_id, entry.participation_type);
+        pending.pending.push(entry);
+        pending.save()
+    }
+
+    pub fn get(&self, task_id: u64, participation_type: ParticipationType) -> Option<&PendingResult> {
+        self.pending
+            .iter()
+            .find(|entry| entry.task_id == task_id && entry.participation_type == participation_type)
+    }
+
+    fn remove(&mut self, task_id: u64, participation_type: ParticipationType) {
+        self.pending
+            .retain(|entry| entry.task_id != task_id || entry.participation_type != participation_type);
+    }
+
+    /// forgets the pending submission once its result arrived
+    pub fn resolve(task_id: u64, participation_type: ParticipationType) -> Result<()> {
+        let mut pending = Self::load()?;
+        if pending.get(task_id, participation_type).is_some() {
+            pending.remove(task_id, participation_type);
+            pending.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// advisory lock held while submit commits and pushes, so two submits can't race on the same index.
+/// the lock file is removed when the guard is dropped.
+#[derive(Debug)]
+pub struct SubmitLock {
+    path: PathBuf,
+}
+
+impl SubmitLock {
+    pub fn acquire(workdir: &Path) -> Result<Self> {
+        fs::create_dir_all(workdir.join(METADATA_DIR))?;
+        let path = workdir.join(METADATA_DIR).join(SUBMIT_LOCK_FILE);
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                write!(file, "{}", process::id())?;
+                debug!("acquired {}", path.display());
+                Ok(Self { path })
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let age = fs::metadata(&path)?
+                    .modified()
+                    .ok()
+                    .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                    .unwrap_or_default();
+                if age > STALE_LOCK_AGE {
+                    warn!("taking over stale lock {}", path.display());
+                    fs::remove_file(&path)?;
+                    return Self::acquire(workdir);
+                }
+
+                let holder = fs::read_to_string(&path).unwrap_or_default();
+                bail!(
+                    "another submit (pid {}) is running in this repository, if it isn't remove {}",
+                    holder.trim(),
+                    path.display()
+                )
+            }
+            Err(e) => Err(e).with_context(|| format!("can't create {}", path.display())),
+        }
+    }
+}
+
+impl Drop for SubmitLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("can't remove {}: {}", self.path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+
+/// a single STOMP 1.2 frame, only as much of the protocol as is needed to subscribe to the live updates
+/// artemis sends over its websocket. bodies are always text for artemis
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub(crate) command: String,
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) body: String,
+}
+
+impl Frame {
+    pub fn new(command: &str, headers: &[(&str, &str)]) -> Self {
+        Self {
+            command: command.to_string(),
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: String::new(),
+        }
+    }
+
+    pub fn connect(host: &str) -> Self {
+        Self::new("CONNECT", &[("accept-version", "1.2"), ("host", host), ("heart-beat", "0,0")])
+    }
+
+    pub fn subscribe(id: &str, destination: &str) -> Self {
+        Self::new("SUBSCRIBE", &[("id", id), ("destination", destination)])
+    }
+
+    pub fn encode(&self) -> String {
+        let mut frame = format!("{}\n", self.command);
+        for (key, value) in &self.headers {
+            frame.push_str(&format!("{}:{}\n", key, value));
+        }
+        frame.push('\n');
+        frame.push_str(&self.body);
+        frame.push('\0');
+        frame
+    }
+
+    /// parses a frame as received in a websocket message. returns None for heart-beats, which are
+    /// just a newline
+    pub fn decode(text: &str) -> Result<Option<Self>> {
+        let text = text.trim_start_matches(['\n', '\r']);
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let (head, body) = text.split_once("\n\n").ok_or(anyhow!("malformed STOMP frame: {:?}", text))?;
+        let mut lines = head.lines();
+        let command = lines.next().unwrap_or_default().to_string();
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        Ok(Some(Self {
+            command,
+            headers,
+            body: body.trim_end_matches('\0').to_string(),
+        }))
+    }
+}
@@ -0,0 +1,217 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{collections::BTreeMap, env, fmt::Display, fs, path::PathBuf, sync::OnceLock};
+
+use anyhow::{Result, anyhow, bail};
+use argon2::Argon2;
+use chacha20poly1305::{
+    Key, XChaCha20Poly1305, XNonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng, rand_core::RngCore},
+};
+use clap::ValueEnum;
+use colored::Colorize;
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{ArtemisConfig, data_dir},
+    prompt::read_secret,
+};
+
+const SERVICE: &str = "artemiscli";
+/// environment variable that holds the passphrase of the file store, so it can be used without a terminal
+pub const PASSPHRASE_VAR: &str = "ARTEMIS_CLI_PASSPHRASE";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// the passphrase of the file store, asked for at most once per invocation
+static PASSPHRASE: OnceLock<String> = OnceLock::new();
+/// whether the keyring can be used, probed at most once per invocation
+static KEYRING_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+/// where the username, password and login tokens are kept
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStore {
+    /// the keyring of the operating system, e.g. the secret service on linux
+    #[default]
+    Keyring,
+    /// a file encrypted with a passphrase, for systems without a keyring
+    File,
+}
+
+impl Display for CredentialStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialStore::Keyring => write!(f, "keyring"),
+            CredentialStore::File => write!(f, "file"),
+        }
+    }
+}
+
+/// reads and writes credentials in the configured store
+#[derive(Clone, Copy, Debug)]
+pub struct Credentials {
+    store: CredentialStore,
+}
+
+impl Credentials {
+    /// uses the configured store, or the file store if the keyring is configured but not available
+    pub fn new(cfg: &ArtemisConfig) -> Self {
+        let store = match cfg.get_credential_store() {
+            CredentialStore::Keyring if !keyring_available() => CredentialStore::File,
+            store => store,
+        };
+        Self { store }
+    }
+
+    pub fn store(&self) -> CredentialStore {
+        self.store
+    }
+
+    /// the value stored under `key`, None if there is none
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        match self.store {
+            CredentialStore::Keyring => match Entry::new(SERVICE, key).and_then(|entry| entry.get_password()) {
+                Ok(value) => Ok(Some(value)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(e) => Err(keyring_error(e)),
+            },
+            CredentialStore::File => Ok(read_file()?.remove(key)),
+        }
+    }
+
+    pub fn set(&self, key: &str, value: &str) -> Result<()> {
+        match self.store {
+            CredentialStore::Keyring => Entry::new(SERVICE, key)
+                .and_then(|entry| entry.set_password(value))
+                .map_err(keyring_error),
+            CredentialStore::File => {
+                let mut credentials = read_file()?;
+                credentials.insert(key.to_string(), value.to_string());
+                write_file(&credentials)
+            }
+        }
+    }
+
+    /// removes `key`, does nothing if it isn't stored
+    pub fn delete(&self, key: &str) -> Result<()> {
+        match self.store {
+            CredentialStore::Keyring => match Entry::new(SERVICE, key).and_then(|entry| entry.delete_credential()) {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(keyring_error(e)),
+            },
+            CredentialStore::File => {
+                let mut credentials = read_file()?;
+                if credentials.remove(key).is_some() {
+                    write_file(&credentials)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// reads a missing entry to find out whether the keyring works, warns once if it doesn't
+fn keyring_available() -> bool {
+    *KEYRING_AVAILABLE.get_or_init(|| match Entry::new(SERVICE, "probe").and_then(|entry| entry.get_password()) {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(e) => {
+            eprintln!(
+                "{} can't access the keyring ({}), using the encrypted file store instead. make this permanent \
+                with 'artemis-cli config credential-store file'",
+                "warning:".yellow().bold(),
+                e
+            );
+            false
+        }
+    })
+}
+
+fn keyring_error(e: keyring::Error) -> anyhow::Error {
+    anyhow!(
+        "can't access the keyring ({}), without a secret service daemon use the encrypted file store instead: \
+        'artemis-cli config credential-store file'",
+        e
+    )
+}
+
+fn file_path() -> PathBuf {
+    data_dir().join("credentials.enc")
+}
+
+fn passphrase() -> Result<&'static str> {
+    if let Some(passphrase) = PASSPHRASE.get() {
+        return Ok(passphrase);
+    }
+    let passphrase = match env::var(PASSPHRASE_VAR) {
+        Ok(passphrase) if !passphrase.is_empty() => passphrase,
+        _ => read_secret("passphrase for the credential file: ")?,
+    };
+    Ok(PASSPHRASE.get_or_init(|| passphrase))
+}
+
+fn cipher(salt: &[u8]) -> Result<XChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase()?.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("can't derive the key of the credential file: {}", e))?;
+    Ok(XChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+/// the file consists of the salt, the nonce and the encrypted JSON map of all credentials
+fn read_file() -> Result<BTreeMap<String, String>> {
+    let path = file_path();
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let data = fs::read(&path)?;
+    if data.len() < SALT_LEN + NONCE_LEN {
+        bail!("{} is corrupted, delete it and configure your credentials again", path.display());
+    }
+    let (salt, rest) = data.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let plaintext = cipher(salt)?
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("can't decrypt {}, is the passphrase correct?", path.display()))?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+fn write_file(credentials: &BTreeMap<String, String>) -> Result<()> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(&salt)?
+        .encrypt(&nonce, serde_json::to_vec(credentials)?.as_slice())
+        .map_err(|_| anyhow!("can't encrypt the credentials"))?;
+
+    let mut data = salt.to_vec();
+    data.extend_from_slice(&nonce);
+    data.extend(ciphertext);
+
+    let path = file_path();
+    fs::create_dir_all(data_dir())?;
+    fs::write(&path, data)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
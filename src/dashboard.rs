@@ -0,0 +1,191 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use anyhow::Result;
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListState},
+};
+
+use crate::{core::adapter::Course, output::task_status, time_format::format_date_time};
+
+/// what to do with the highlighted task after the dashboard was closed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DashboardAction {
+    Start(u64),
+    Submit(u64),
+}
+
+/// courses in the left pane, the tasks of the selected course in the right one
+struct Dashboard<'a> {
+    courses: &'a [Course],
+    course: ListState,
+    task: ListState,
+    /// whether the arrow keys move through the tasks instead of the courses
+    tasks_focused: bool,
+}
+
+impl<'a> Dashboard<'a> {
+    fn new(courses: &'a [Course]) -> Self {
+        Self {
+            courses,
+            course: ListState::default().with_selected((!courses.is_empty()).then_some(0)),
+            task: ListState::default(),
+            tasks_focused: false,
+        }
+    }
+
+    fn selected_course(&self) -> Option<&'a Course> {
+        self.course.selected().and_then(|index| self.courses.get(index))
+    }
+
+    fn selected_task_id(&self) -> Option<u64> {
+        let course = self.selected_course()?;
+        self.task.selected().and_then(|index| course.tasks.get(index)).map(|task| task.id)
+    }
+
+    /// handles input until the user quits or picks an action for a task
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<Option<DashboardAction>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                KeyCode::Up | KeyCode::Char('k') if self.tasks_focused => self.task.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') if self.tasks_focused => self.task.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.select_course(|course| course.select_previous()),
+                KeyCode::Down | KeyCode::Char('j') => self.select_course(|course| course.select_next()),
+                KeyCode::Right | KeyCode::Tab | KeyCode::Enter if !self.tasks_focused => {
+                    self.tasks_focused = true;
+                    if self.task.selected().is_none() {
+                        self.task.select_first();
+                    }
+                }
+                KeyCode::Left | KeyCode::Tab => self.tasks_focused = false,
+                KeyCode::Char('s') => {
+                    if let Some(id) = self.selected_task_id() {
+                        return Ok(Some(DashboardAction::Start(id)));
+                    }
+                }
+                KeyCode::Char('u') => {
+                    if let Some(id) = self.selected_task_id() {
+                        return Ok(Some(DashboardAction::Submit(id)));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn select_course(&mut self, select: impl FnOnce(&mut ListState)) {
+        select(&mut self.course);
+        self.task = ListState::default();
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(main);
+        let highlight = Style::default().add_modifier(Modifier::REVERSED);
+        let border = |focused: bool| if focused { Style::default().bold() } else { Style::default().dim() };
+
+        let courses = List::new(self.courses.iter().map(|course| format!("{:<5} {}", course.id, course.title)))
+            .block(Block::bordered().title("courses").border_style(border(!self.tasks_focused)))
+            .highlight_style(highlight);
+        frame.render_stateful_widget(courses, left, &mut self.course);
+
+        let tasks = self.selected_course().map(|course| course.tasks.as_slice()).unwrap_or_default();
+        let rows = tasks.iter().map(|task| {
+            let due = task
+                .due_date
+                .map(|due| format_date_time(&due))
+                .unwrap_or_default();
+            let row = format!("{:<6} {:<40} {:<26} {}", task.id, task.title, task_status(task), due);
+            match task {
+                task if task.completed => Line::from(row).green(),
+                task if task.is_overdue() => Line::from(row).red(),
+                _ => Line::from(row),
+            }
+        });
+        let tasks = List::new(rows)
+            .block(Block::bordered().title("tasks").border_style(border(self.tasks_focused)))
+            .highlight_style(highlight);
+        frame.render_stateful_widget(tasks, right, &mut self.task);
+
+        let keys = "↑/↓ select  ←/→ switch pane  s start task  u submit task  q quit";
+        frame.render_widget(Line::from(keys).dim(), help);
+    }
+}
+
+/// shows the dashboard until the user quits, returns the action picked for a task if any
+pub fn run_dashboard(courses: &[Course]) -> Result<Option<DashboardAction>> {
+    let mut terminal = ratatui::init();
+    let result = Dashboard::new(courses).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend};
+    use serde_json::{Value, json};
+
+    use super::Dashboard;
+    use crate::core::adapter::Adapter;
+
+    fn course() -> Value {
+        json!({
+            "id": 12,
+            "title": "Algorithms",
+            "exercises": [{ "id": 345, "title": "Sorting", "maxPoints": 10.0 }],
+        })
+    }
+
+    /// renders the dashboard of the given courses page and returns the screen line by line
+    fn render(page: Value) -> Vec<String> {
+        let courses = Adapter::parse_courses(&page.to_string()).unwrap();
+        let mut dashboard = Dashboard::new(&courses);
+        let mut terminal = Terminal::new(TestBackend::new(120, 8)).unwrap();
+        terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    fn assert_shows_course(screen: &[String]) {
+        assert!(screen.iter().any(|line| line.contains("12    Algorithms")), "{:#?}", screen);
+        assert!(screen.iter().any(|line| line.contains("345    Sorting")), "{:#?}", screen);
+    }
+
+    #[test]
+    fn courses_wrapped_in_course_object_are_shown() {
+        assert_shows_course(&render(json!({ "courses": [{ "course": course() }] })));
+    }
+
+    #[test]
+    fn courses_at_top_level_are_shown() {
+        assert_shows_course(&render(json!({ "courses": [course()] })));
+    }
+}
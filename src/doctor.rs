@@ -0,0 +1,232 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{env, process::Command, time::Duration};
+
+use colored::Colorize;
+use log::debug;
+use reqwest::{Client, StatusCode};
+
+use crate::{
+    config::ArtemisConfig,
+    core::adapter::{AuthMode, CloneProtocol},
+    credentials::{CredentialStore, Credentials, PASSPHRASE_VAR},
+};
+
+/// the outcome of a single environment check, `problem` holds a remediation hint if the check failed
+struct Check {
+    name: &'static str,
+    problem: Option<String>,
+    /// why the check doesn't apply to the configuration, skipped checks neither pass nor fail
+    skipped: Option<String>,
+    /// a problem that doesn't break the configuration, shown but not counted as failure
+    warning: bool,
+}
+
+impl Check {
+    fn ok(name: &'static str) -> Self {
+        Self {
+            name,
+            problem: None,
+            skipped: None,
+            warning: false,
+        }
+    }
+
+    fn failed(name: &'static str, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            problem: Some(hint.into()),
+            skipped: None,
+            warning: false,
+        }
+    }
+
+    fn skipped(name: &'static str, reason: impl Into<String>) -> Self {
+        Self {
+            name,
+            problem: None,
+            skipped: Some(reason.into()),
+            warning: false,
+        }
+    }
+
+    /// turns a failure into a warning
+    fn optional(self) -> Self {
+        Self { warning: true, ..self }
+    }
+}
+
+/// runs all checks, prints a checklist and returns whether every check passed
+pub async fn run_doctor(cfg: &ArtemisConfig) -> bool {
+    let base_url = cfg.get_base_url().as_str();
+    let client = match Client::builder()
+        .user_agent(cfg.get_user_agent().as_str())
+        .timeout(Duration::from_secs(10))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            println!("{} can't build the http client: {}", "✗".bold().red(), e);
+            return false;
+        }
+    };
+
+    let credentials = Credentials::new(cfg);
+    let mut checks = vec![check_credential_store(&credentials)];
+    checks.extend(check_credentials(&credentials));
+    checks.push(check_git_identity());
+    // repositories served over https are cloned with the session, they only need ssh if the user chose it
+    let ssh_agent = check_ssh_agent();
+    checks.push(match cfg.get_clone_protocol() {
+        CloneProtocol::Ssh => ssh_agent,
+        CloneProtocol::Https => ssh_agent.optional(),
+    });
+    checks.push(check_base_url(&client, base_url).await);
+    checks.push(check_token(cfg, &client, base_url, &credentials).await);
+
+    for check in &checks {
+        match (&check.problem, &check.skipped) {
+            (Some(hint), _) if check.warning => {
+                println!("{} {}", "!".bold().yellow(), check.name);
+                println!("    {}", hint);
+            }
+            (Some(hint), _) => {
+                println!("{} {}", "✗".bold().red(), check.name);
+                println!("    {}", hint);
+            }
+            (None, Some(reason)) => println!("{} {} ({})", "-".dimmed(), check.name.dimmed(), reason),
+            (None, None) => println!("{} {}", "✓".bold().green(), check.name),
+        }
+    }
+
+    let failed = checks.iter().filter(|check| check.problem.is_some() && !check.warning).count();
+    if failed == 0 {
+        println!("{}", "everything looks good".green());
+    } else {
+        println!("{}", format!("{} of {} checks failed", failed, checks.len()).red());
+    }
+    failed == 0
+}
+
+fn check_credential_store(credentials: &Credentials) -> Check {
+    match (credentials.store(), credentials.get("username")) {
+        (CredentialStore::Keyring, Ok(_)) => Check::ok("keyring is accessible"),
+        (CredentialStore::Keyring, Err(e)) => Check::failed(
+            "keyring is accessible",
+            format!("{:#}, or make sure a secret service daemon (e.g. gnome-keyring) is running", e),
+        ),
+        (CredentialStore::File, Ok(_)) => Check::ok("credential file can be decrypted"),
+        (CredentialStore::File, Err(e)) => Check::failed(
+            "credential file can be decrypted",
+            format!("{:#}, the passphrase can also be given in ${}", e, PASSPHRASE_VAR),
+        ),
+    }
+}
+
+fn check_credentials(credentials: &Credentials) -> Vec<Check> {
+    [
+        ("username is configured", "username", "artemis-cli config username [USERNAME]"),
+        ("password is configured", "password", "artemis-cli config password [PASSWORD]"),
+    ]
+    .into_iter()
+    .map(|(name, key, command)| match credentials.get(key) {
+        Ok(Some(_)) => Check::ok(name),
+        _ => Check::failed(name, format!("run '{}'", command)),
+    })
+    .collect()
+}
+
+fn check_git_identity() -> Check {
+    const NAME: &str = "git user.name and user.email are set";
+    let config = match git2::Config::open_default() {
+        Ok(config) => config,
+        Err(e) => return Check::failed(NAME, format!("can't open the git config: {}", e)),
+    };
+
+    let mut missing = Vec::new();
+    if config.get_string("user.name").is_err() {
+        missing.push("git config --global user.name 'YourName'");
+    }
+    if config.get_string("user.email").is_err() {
+        missing.push("git config --global user.email 'YourEmail'");
+    }
+
+    if missing.is_empty() {
+        Check::ok(NAME)
+    } else {
+        Check::failed(NAME, format!("run {}", missing.join(" and ")))
+    }
+}
+
+fn check_ssh_agent() -> Check {
+    const NAME: &str = "ssh-agent is running and has keys";
+    if env::var_os("SSH_AUTH_SOCK").is_none() {
+        return Check::failed(NAME, "SSH_AUTH_SOCK is not set, start the agent with 'eval $(ssh-agent)'");
+    }
+
+    match Command::new("ssh-add").arg("-l").output() {
+        Ok(output) if output.status.success() => Check::ok(NAME),
+        Ok(output) => {
+            debug!("ssh-add -l: {}", String::from_utf8_lossy(&output.stdout));
+            Check::failed(NAME, "the agent has no keys, add yours with 'ssh-add [KEYFILE]'")
+        }
+        Err(e) => Check::failed(NAME, format!("can't run ssh-add ({}), is OpenSSH installed?", e)),
+    }
+}
+
+async fn check_base_url(client: &Client, base_url: &str) -> Check {
+    const NAME: &str = "base url is reachable";
+    match client.get(base_url).send().await {
+        Ok(_) => Check::ok(NAME),
+        Err(e) => Check::failed(
+            NAME,
+            format!(
+                "can't reach {} ({}), check your connection or run 'artemis-cli config base-url [BASEURL]'",
+                base_url, e
+            ),
+        ),
+    }
+}
+
+async fn check_token(cfg: &ArtemisConfig, client: &Client, base_url: &str, credentials: &Credentials) -> Check {
+    const NAME: &str = "stored login token is valid";
+    if !cfg.get_remember_me() {
+        return Check::skipped(NAME, "remember-me is off, the token isn't stored");
+    }
+    let auth_mode = cfg.get_auth_mode();
+    let token = match credentials.get(auth_mode.session_key()) {
+        Ok(Some(token)) => token,
+        _ => return Check::failed(NAME, "no token stored yet, run e.g. 'artemis-cli list-courses' to log in"),
+    };
+
+    let request = client.get(format!("{}/api/public/account", base_url));
+    let request = match auth_mode {
+        AuthMode::Cookie => request.header(reqwest::header::COOKIE, token),
+        AuthMode::Bearer => request.bearer_auth(token),
+    };
+    let response = request.header(reqwest::header::ACCEPT, "application/json").send().await;
+
+    match response {
+        Ok(response) if response.status().is_success() => Check::ok(NAME),
+        Ok(response) if response.status() == StatusCode::UNAUTHORIZED => {
+            Check::failed(NAME, "the token has expired, it is renewed on the next login")
+        }
+        Ok(response) => Check::failed(NAME, format!("unexpected response from artemis: {}", response.status())),
+        Err(e) => Check::failed(NAME, format!("can't verify the token: {}", e)),
+    }
+}
@@ -0,0 +1,46 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{path::Path, process::Command};
+
+use anyhow::{Result, anyhow, bail};
+use log::info;
+
+use crate::config::ArtemisConfig;
+
+/// opens `path` with the configured editor and waits for it to exit
+pub fn open_in_editor(cfg: &ArtemisConfig, path: &Path) -> Result<()> {
+    let editor = cfg
+        .get_editor()
+        .ok_or(anyhow!("no editor configured, run 'artemis-cli config editor [COMMAND]' or set $EDITOR"))?;
+
+    // the editor may be given with arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().ok_or(anyhow!("the configured editor is empty"))?;
+
+    info!("opening {} with {}", path.display(), editor);
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| anyhow!("can't start editor '{}': {}", editor, e))?;
+
+    if !status.success() {
+        bail!("editor '{}' exited with {}", editor, status);
+    }
+    Ok(())
+}
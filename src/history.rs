@@ -0,0 +1,104 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::config::data_dir;
+
+/// only this many invocations are kept, older ones are dropped
+const MAX_HISTORY: usize = 200;
+/// replaces secrets given on the command line
+const MASK: &str = "********";
+
+/// one run of artemis-cli
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Invocation {
+    pub(crate) time: DateTime<Local>,
+    /// the arguments without the name of the binary
+    pub(crate) args: Vec<String>,
+}
+
+/// the most recent invocations, oldest first
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CommandHistory {
+    #[serde(default)]
+    invocations: Vec<Invocation>,
+}
+
+impl CommandHistory {
+    fn path() -> PathBuf {
+        data_dir().join("history.toml")
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(&path).with_context(|| format!("can't read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("can't parse {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        fs::create_dir_all(data_dir())?;
+        fs::write(&path, toml::to_string(self)?).with_context(|| format!("can't write {}", path.display()))
+    }
+
+    /// appends the invocation with `args` and saves the history, passwords are masked
+    pub fn record(args: impl IntoIterator<Item = String>) -> Result<()> {
+        let mut history = Self::load()?;
+        history.invocations.push(Invocation {
+            time: Local::now(),
+            args: mask_secrets(args.into_iter().skip(1).collect()),
+        });
+        let excess = history.invocations.len().saturating_sub(MAX_HISTORY);
+        history.invocations.drain(..excess);
+        history.save()
+    }
+
+    /// the last `count` invocations, most recent first
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = &Invocation> {
+        self.invocations.iter().rev().take(count)
+    }
+
+    /// the invocation `index` runs ago, 1 is the most recent one
+    pub fn get(&self, index: usize) -> Result<&Invocation> {
+        let invocation = index
+            .checked_sub(1)
+            .and_then(|offset| self.invocations.iter().rev().nth(offset))
+            .ok_or(anyhow!("there is no command {} in the history", index))?;
+        if invocation.args.iter().any(|arg| arg == MASK) {
+            bail!("command {} contained a password and can't be replayed", index);
+        }
+        Ok(invocation)
+    }
+}
+
+/// the value after `config password` is the only secret that can be given as an argument
+fn mask_secrets(mut args: Vec<String>) -> Vec<String> {
+    if let Some(position) = args.iter().position(|arg| arg == "password")
+        && args[..position].iter().any(|arg| arg == "config")
+    {
+        args.iter_mut().skip(position + 1).for_each(|arg| *arg = MASK.to_string());
+    }
+    args
+}
@@ -0,0 +1,84 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{path::Path, time::Duration};
+
+use anyhow::{Result, bail};
+use colored::Colorize;
+use reqwest::Client;
+
+use crate::{
+    config::ArtemisConfig,
+    core::adapter::Adapter,
+    credentials::Credentials,
+    prompt::{ask, confirm, read_secret},
+};
+
+/// walks through the first time setup: base url, username and password, followed by a test login.
+/// the config is saved to `path` before logging in so a failed login doesn't lose the answers.
+pub async fn run_init(cfg: &mut ArtemisConfig, path: Option<&Path>) -> Result<()> {
+    println!("setting up artemis-cli, press enter to keep the value in brackets");
+
+    loop {
+        let base_url = ask("base url of your artemis instance", Some(cfg.get_base_url()))?;
+        if let Err(e) = cfg.set_base_url(base_url) {
+            println!("{} {:#}", "✗".bold().red(), e);
+            continue;
+        }
+        match check_reachable(cfg).await {
+            Ok(()) => {
+                println!("{} {} is reachable", "✓".bold().green(), cfg.get_base_url());
+                break;
+            }
+            Err(e) => {
+                println!("{} can't reach {}: {:#}", "✗".bold().red(), cfg.get_base_url(), e);
+                if confirm("use it anyway?")? {
+                    break;
+                }
+            }
+        }
+    }
+
+    let credentials = Credentials::new(cfg);
+    let current_username = credentials.get("username").ok().flatten();
+    let username = ask("username", current_username.as_deref())?;
+    let password = read_secret("password: ")?;
+    if password.is_empty() {
+        bail!("the password can't be empty");
+    }
+
+    cfg.save(path);
+    credentials.set("username", &username)?;
+    credentials.set("password", &password)?;
+
+    // a session of the previous account must not be reused for the test login
+    Adapter::forget_session(cfg)?;
+    let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+    let account = s.get_account().await?;
+    println!("{} logged in as {} ({})", "✓".bold().green(), account.name, account.login);
+    println!("you're all set, run 'artemis-cli list-courses' to get started");
+    Ok(())
+}
+
+async fn check_reachable(cfg: &ArtemisConfig) -> Result<()> {
+    let client = Client::builder()
+        .user_agent(cfg.get_user_agent().as_str())
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    client.get(cfg.get_base_url()).send().await?;
+    Ok(())
+}
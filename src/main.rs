@@ -17,24 +17,76 @@ You should have received a copy of the GNU General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-use std::env;
+use std::{
+    collections::HashSet,
+    env,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Local};
 use clap::Parser;
-use colored::{self, Colorize};
-use env_logger;
-use keyring::Entry;
+use colored::Colorize;
 use log::{self, LevelFilter, info, trace, warn};
-use tokio;
 
 use crate::{
+    clean::run_clean,
     cli::{Cli, Commands, ConfigCommands},
-    config::ArtemisConfig,
-    core::{adapter::Adapter, git::ArtemisRepo},
+    config::{ArtemisConfig, university_base_url},
+    credentials::Credentials,
+    dashboard::{DashboardAction, run_dashboard},
+    core::{
+        adapter::{
+            Adapter, BuildStillRunning, ExampleSolution, LoginRejected, MissingCredentials, ParticipationType,
+            TaskResult,
+        },
+        git::{ArtemisRepo, DEFAULT_REMOTE, Identity, PullOutcome, sanitize_path_segment},
+        metadata::{CloneRegistry, PendingResult, PendingResults, TaskIndex},
+    },
+    doctor::run_doctor,
+    editor::open_in_editor,
+    history::CommandHistory,
+    init::run_init,
+    output::{
+        OutputOptions, TaskListOptions, print_account, print_announcements, print_attempts, print_competencies,
+        print_config_diff, print_course_tasks, print_course_tasks_json, print_course_totals, print_courses, print_diff,
+        print_grades, print_hints, print_history, print_json_schemas, print_next_task, print_team, print_test_results,
+        set_output_width,
+    },
+    prompt::{confirm, set_assume_yes},
+    time_format::{format_date_time, set_time_format},
+    watch::run_watch,
 };
+mod clean;
 mod cli;
 mod config;
 mod core;
+mod credentials;
+mod dashboard;
+mod doctor;
+mod editor;
+mod history;
+mod init;
+mod output;
+mod pager;
+mod prompt;
+mod time_format;
+mod watch;
+
+/// the smallest refresh interval in seconds allowed for list-tasks --watch
+const MIN_WATCH_INTERVAL: u64 = 10;
+/// exit code when a login is needed but the username or password isn't configured
+const EXIT_MISSING_CREDENTIALS: i32 = 3;
+/// exit code when the result of a submission didn't arrive in time
+const EXIT_BUILD_STILL_RUNNING: i32 = 4;
+/// delay between polls for the result of a submission
+const RESULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// how long the dashboard waits for the result of the pushed commit before giving up
+const RESULT_MAX_WAIT: Duration = Duration::from_secs(120);
 
 fn init_log(verbosity: u8) {
     let log_level = match verbosity {
@@ -45,83 +97,893 @@ fn init_log(verbosity: u8) {
         4 => LevelFilter::Debug,
         _ => LevelFilter::Trace,
     };
-    env_logger::builder()
-        .filter_level(log_level)
-        .target(env_logger::Target::Stdout)
-        .init();
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(log_level);
+    // RUST_LOG refines the baseline set by -v, e.g. RUST_LOG=artemis_cli=debug,reqwest=warn
+    if let Ok(filters) = env::var("RUST_LOG") {
+        builder.parse_filters(&filters);
+    }
+    builder.target(env_logger::Target::Stdout).init();
+}
+
+fn output_options(cli: &Cli) -> OutputOptions {
+    OutputOptions {
+        json: cli.json,
+        quiet: cli.quiet,
+        only_failed: cli.only_failed,
+        explain: false,
+        sort_tests: cli.sort_tests,
+        pager: !cli.no_pager,
+    }
+}
+
+/// waits for the result of `commit` by polling, a result pushed over the websocket is fetched right away instead
+/// of at the next poll. fails with `BuildStillRunning` once `max_wait` has passed without a result
+async fn wait_for_result(
+    s: &mut Adapter,
+    taskid: u64,
+    participation_type: ParticipationType,
+    participation_id: Option<u64>,
+    commit: &str,
+    pushed_at: DateTime<Local>,
+    max_wait: Duration,
+) -> Result<TaskResult> {
+    let started = Instant::now();
+    // the socket may stay silent, e.g. behind a proxy that drops it or if the result came before the subscription,
+    // so it only shortens the wait and polling goes on regardless
+    let push = s.result_push(commit)?;
+    tokio::pin!(push);
+    let mut listening = true;
+
+    let mut poll = 0;
+    loop {
+        tokio::select! {
+            pushed = &mut push, if listening => {
+                listening = false;
+                match pushed {
+                    Ok(()) => info!("a result was pushed, fetching it"),
+                    Err(e) => {
+                        info!("no live results: {:#}", e);
+                        continue;
+                    }
+                }
+            }
+            // give artemis some time to build and test the submission
+            _ = tokio::time::sleep(RESULT_POLL_INTERVAL) => {}
+        }
+        poll += 1;
+
+        let result = latest_result(s, taskid, participation_type, participation_id).await;
+        let timed_out = started.elapsed() >= max_wait;
+        match result {
+            Ok(result) if result.is_for_commit(commit, pushed_at) => return Ok(result),
+            Ok(_) if timed_out => return Err(BuildStillRunning.into()),
+            Err(e) if timed_out => {
+                info!("poll {}: {:#}", poll, e);
+                return Err(BuildStillRunning.into());
+            }
+            Ok(_) => info!("poll {}: no result for commit {} yet", poll, commit),
+            Err(e) => info!("poll {}: {:#}", poll, e),
+        }
+    }
+}
+
+/// the latest result, fetched from the participation directly if its id is known
+async fn latest_result(
+    s: &mut Adapter,
+    taskid: u64,
+    participation_type: ParticipationType,
+    participation_id: Option<u64>,
+) -> Result<TaskResult> {
+    let Some(participation_id) = participation_id else {
+        return s.get_latest_test_result(taskid, participation_type).await;
+    };
+    match s.get_participation_test_result(participation_id, None).await {
+        Ok(result) => Ok(result),
+        // e.g. an outdated id from the task index after the exercise was reset, the exercise knows the current one
+        Err(e) => {
+            info!("can't fetch the results of participation {}: {:#}", participation_id, e);
+            s.get_latest_test_result(taskid, participation_type).await
+        }
+    }
+}
+
+/// the id of the graded participation of the task as recorded by the last dashboard fetch, if it isn't too old
+fn indexed_participation_id(cfg: &ArtemisConfig, taskid: u64, participation_type: ParticipationType) -> Option<u64> {
+    if participation_type != ParticipationType::Graded {
+        return None;
+    }
+    match TaskIndex::lookup(taskid, cfg.get_task_cache_max_age()) {
+        Ok(task) => task.and_then(|task| task.participation_id),
+        Err(e) => {
+            warn!("can't read the task index: {:#}", e);
+            None
+        }
+    }
+}
+
+/// starts the participation and clones its repository into `dir`, the configured clone-dir-template or the
+/// default directory, tasks whose prerequisites aren't completed are refused
+async fn start_task(
+    cfg: &ArtemisConfig,
+    s: &mut Adapter,
+    taskid: u64,
+    participation_type: ParticipationType,
+    dir: Option<&Path>,
+    template: Option<&str>,
+) -> Result<PathBuf> {
+    let max_age = cfg.get_task_cache_max_age();
+    let mut task = TaskIndex::lookup(taskid, max_age)?;
+    // completing a prerequisite unlocks the task, so a cached lock is always checked again
+    if task.as_ref().is_none_or(|task| !task.locked_by.is_empty()) {
+        // fetching the dashboard refreshes the index
+        s.get_all_courses().await?;
+        task = TaskIndex::lookup(taskid, max_age)?;
+    }
+    if let Some(task) = &task
+        && !task.locked_by.is_empty()
+    {
+        bail!("task {} is locked, complete {} first", taskid, task.locked_by.join(", "));
+    }
+
+    let path = match (dir, cfg.get_clone_dir_template()) {
+        (Some(dir), _) => dir.to_path_buf(),
+        (None, Some(clone_dir_template)) => {
+            let task = task.ok_or(anyhow!("task {} isn't part of any of your courses", taskid))?;
+            ArtemisRepo::dir_from_template(clone_dir_template, &task, participation_type)?
+        }
+        (None, None) => ArtemisRepo::default_dir(taskid, participation_type)?,
+    };
+    // keep auxiliary repositories next to the student repository instead of in its place
+    let path = match template.filter(|_| dir.is_none()) {
+        Some(name) => {
+            let mut path = path.into_os_string();
+            path.push(format!("-{}", sanitize_path_segment(name)));
+            PathBuf::from(path)
+        }
+        None => path,
+    };
+    let repo_uri = s
+        .start_artemis_task(taskid, participation_type, template)
+        .await
+        .context("couldn't start the task and fetch its url")?;
+    let repo = ArtemisRepo::create(&repo_uri, taskid, participation_type, template, &path, s.git_http_auth())
+        .context("couldn't create the repository")?;
+    if repo.uses_lfs()
+        && let Err(e) = repo.fetch_lfs()
+    {
+        eprintln!(
+            "{} this repository uses Git LFS but its files couldn't be fetched ({}), \
+             run 'git lfs install && git lfs pull' inside the repository",
+            "warning:".yellow().bold(),
+            e
+        );
+    }
+    // auxiliary repositories are read-only for students
+    if template.is_none() {
+        let identity = commit_identity(cfg, s).await;
+        repo.commit_and_push(&identity, None, DEFAULT_REMOTE).context("can't commit and push to remote repository")?;
+    }
+    Ok(path)
+}
+
+/// reminds of the submission policy of the task before submitting
+async fn print_submission_policy(s: &mut Adapter, taskid: u64) {
+    match s.get_submission_policy(taskid).await {
+        Ok(Some(policy)) => eprintln!("{} {}", "note:".yellow().bold(), policy),
+        Ok(None) => {}
+        Err(e) => info!("can't fetch the submission policy: {:#}", e),
+    }
+}
+
+/// commits and pushes the working tree and records the submission as awaiting its result
+async fn submit(
+    cfg: &ArtemisConfig,
+    s: &mut Adapter,
+    repo: &ArtemisRepo,
+    taskid: u64,
+    message: Option<&str>,
+    remote: &str,
+) -> Result<PendingResult> {
+    if let Some(template) = repo.template() {
+        bail!("this is a clone of the {} repository of the task, only your own repository can be submitted", template);
+    }
+    let identity = commit_identity(cfg, s).await;
+    let lock = repo.lock_submit()?;
+    let commit = repo.commit_and_push(&identity, message, remote)?.to_string();
+    let pending = PendingResult {
+        task_id: taskid,
+        participation_type: repo.participation_type(),
+        commit,
+        pushed_at: Local::now(),
+    };
+    drop(lock);
+    info!("successfully submited task");
+
+    if let Err(e) = PendingResults::add(pending.clone()) {
+        warn!("can't record the pending submission: {:#}", e);
+    }
+    Ok(pending)
+}
+
+/// the directory given with --repo-dir, or the current directory
+fn repo_dir(cli: &Cli) -> Result<PathBuf> {
+    match &cli.repo_dir {
+        Some(dir) => Ok(dir.clone()),
+        None => Ok(env::current_dir()?),
+    }
+}
+
+/// the identity for commits, taken from the commit-author config, the git config or, if that is incomplete, from
+/// the artemis account
+async fn commit_identity(cfg: &ArtemisConfig, s: &mut Adapter) -> Identity {
+    if let Some(identity) = cfg.get_commit_author().or_else(ArtemisRepo::git_identity) {
+        return identity;
+    }
+
+    eprintln!(
+        "{}",
+        "hint: no git identity configured, run git config --global user.name 'YourName' and git config --global user.email 'YourEmail'"
+            .yellow()
+    );
+    match s.get_account().await {
+        Ok(account) => Identity {
+            name: account.name,
+            email: account.email,
+        },
+        Err(e) => {
+            warn!("can't load the artemis account, using a placeholder identity: {}", e);
+            Identity {
+                name: "artemis-cli".to_string(),
+                email: "artemis-cli@localhost".to_string(),
+            }
+        }
+    }
+}
+
+/// re-fetches and prints the tasks of a course every `interval` until an error occurs
+async fn watch_tasks(s: &mut Adapter, courseid: u64, options: &TaskListOptions, interval: Duration) -> Result<()> {
+    loop {
+        let courses = s.get_all_courses().await?;
+        // clear the screen and move the cursor to the top left like watch does
+        print!("\x1B[2J\x1B[H");
+        print_course_tasks(&courses, courseid, options);
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// prints the build logs of the participation, with `follow` new lines are printed as they are appended
+/// until a new result shows that the build completed. without a pending build the logs are printed once
+async fn print_build_logs(
+    s: &mut Adapter,
+    taskid: u64,
+    participation_type: ParticipationType,
+    follow: bool,
+) -> Result<()> {
+    let started = s.get_build_state(taskid, participation_type).await?;
+    if follow && !started.pending {
+        eprintln!("{} no build is queued or running, printing the logs of the last build", "note:".yellow().bold());
+    }
+    // the endpoint always returns the whole log, so remember what was already printed
+    let mut seen = HashSet::new();
+    let participation_id = started.participation_id;
+    loop {
+        let completed = !follow || !started.pending || {
+            let state = s.get_build_state(taskid, participation_type).await?;
+            !state.pending || state.latest_result != started.latest_result
+        };
+        for statement in s.get_build_logs(participation_id, None).await? {
+            if seen.insert((statement.time, statement.log.clone())) {
+                println!("{}", statement);
+            }
+        }
+        if completed {
+            return Ok(());
+        }
+        tokio::time::sleep(RESULT_POLL_INTERVAL).await;
+    }
 }
 
 async fn run_commands(cli: &Cli, cfg: &mut ArtemisConfig) -> Result<()> {
+    if let Some(base_url) = &cli.base_url {
+        cfg.override_base_url(base_url)?;
+    }
+    if let Some(timeout) = cli.timeout_override {
+        cfg.override_timeout(timeout)?;
+    }
+    if cli.fresh_login {
+        cfg.force_fresh_login();
+    }
+    ArtemisRepo::set_network_timeout(Duration::from_secs(cfg.get_timeout()))?;
+
     match cli.command.as_ref().unwrap() {
-        Commands::ListCourses => {
-            let mut s = Adapter::init(30, cfg.get_base_url()).await;
-
-            let courses = s.get_all_courses().await.unwrap();
-            for course in courses {
-                println!("{:<5} {}", course.id, course.title)
-            }
-        }
-        Commands::ListTasks { courseid } => {
-            let mut s = Adapter::init(30, cfg.get_base_url()).await;
-
-            let courses = s.get_all_courses().await.unwrap();
-            for course in courses {
-                if course.id == *courseid {
-                    for task in course.tasks {
-                        println!(
-                            "{:<5} {:<40} {:<15}",
-                            task.id,
-                            task.title,
-                            if task.completed {
-                                "completed"
-                            } else if task.is_active {
-                                "incomplete"
-                            } else {
-                                "not started"
-                            }
+        Commands::ListCourses { count, semester } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+
+            let mut courses = s.get_all_courses().await.unwrap();
+            if let Some(semester) = semester {
+                courses.retain(|course| {
+                    course.semester.as_ref().is_some_and(|other| other.eq_ignore_ascii_case(semester))
+                });
+            }
+            if *count {
+                print_course_totals(&courses);
+                return Ok(());
+            }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&courses)?);
+                return Ok(());
+            }
+            print_courses(&courses);
+        }
+        Commands::ListTasks {
+            courseid,
+            limit,
+            category,
+            include_unreleased,
+            sort,
+            watch,
+            compact,
+        } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let options = TaskListOptions {
+                limit: *limit,
+                category: category.clone(),
+                include_unreleased: *include_unreleased,
+                sort: *sort,
+                compact: *compact,
+            };
+
+            let Some(interval) = watch else {
+                let courses = s.get_all_courses().await?;
+                if cli.json {
+                    print_course_tasks_json(&courses, *courseid, &options)?;
+                } else {
+                    print_course_tasks(&courses, *courseid, &options);
+                }
+                return Ok(());
+            };
+
+            // the dashboard request is expensive, so don't refresh more often than every few seconds
+            let interval = Duration::from_secs((*interval).max(MIN_WATCH_INTERVAL));
+            tokio::select! {
+                result = watch_tasks(&mut s, *courseid, &options, interval) => result?,
+                _ = tokio::signal::ctrl_c() => info!("stopped watching"),
+            }
+        }
+        Commands::Next { courseid } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let courses = s.get_all_courses().await?;
+            let course = courses
+                .iter()
+                .find(|course| course.id == *courseid)
+                .ok_or(anyhow!("you are not enrolled in a course with id {}", courseid))?;
+            print_next_task(course);
+        }
+        Commands::Watch { interval, due_soon } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let interval = Duration::from_secs((*interval).max(MIN_WATCH_INTERVAL));
+            let due_soon = Duration::from_secs(due_soon * 60 * 60);
+            println!("watching your courses, stop with Ctrl-C");
+            tokio::select! {
+                result = run_watch(&mut s, interval, due_soon) => result?,
+                _ = tokio::signal::ctrl_c() => info!("stopped watching"),
+            }
+        }
+        Commands::Dashboard => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let courses = s.get_all_courses().await?;
+            if !io::stdout().is_terminal() {
+                for course in &courses {
+                    println!("{:<5} {}", course.id, course.title);
+                    print_course_tasks(&courses, course.id, &TaskListOptions::default());
+                }
+                return Ok(());
+            }
+
+            match run_dashboard(&courses)? {
+                Some(DashboardAction::Start(taskid)) => {
+                    start_task(cfg, &mut s, taskid, ParticipationType::Graded, None, None).await?;
+                }
+                Some(DashboardAction::Submit(taskid)) => {
+                    // the dashboard submits to the graded participation, practice and template clones are skipped
+                    let registry = CloneRegistry::load()?;
+                    let mut repo = registry
+                        .task_clones(taskid)
+                        .filter(|clone| clone.path.exists())
+                        .filter_map(|clone| ArtemisRepo::open(&clone.path).ok())
+                        .find(|repo| {
+                            repo.participation_type() == ParticipationType::Graded && repo.template().is_none()
+                        })
+                        .ok_or(anyhow!("task {} isn't cloned yet, start it first", taskid))?;
+                    repo.set_http_auth(s.git_http_auth());
+                    print_submission_policy(&mut s, taskid).await;
+                    let pending = submit(cfg, &mut s, &repo, taskid, None, DEFAULT_REMOTE).await?;
+                    let participation_type = pending.participation_type;
+                    let result =
+                        wait_for_result(
+                            &mut s,
+                            taskid,
+                            participation_type,
+                            None,
+                            &pending.commit,
+                            pending.pushed_at,
+                            RESULT_MAX_WAIT,
                         )
+                        .await?;
+                    if result.is_for_commit(&pending.commit, pending.pushed_at) {
+                        PendingResults::resolve(taskid, participation_type)?;
+                    }
+                    print_test_results(&result, output_options(cli), None)?;
+                }
+                None => {}
+            }
+        }
+        Commands::Competencies { courseid } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let competencies = s.get_competencies(*courseid).await?;
+            print_competencies(&competencies);
+        }
+        Commands::Grades { courseid } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let courses = s.get_all_courses().await?;
+            print_grades(&courses, *courseid)?;
+        }
+        Commands::Attempts { courseid } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let courses = s.get_all_courses().await?;
+            print_attempts(&courses, *courseid)?;
+        }
+        Commands::Solution { taskid, dir } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            match s.get_example_solution(*taskid).await? {
+                None => println!("solution not yet available"),
+                Some(ExampleSolution::Text(solution)) => println!("{}", solution),
+                Some(ExampleSolution::Repository(url)) => {
+                    let path = match dir {
+                        Some(dir) => dir.clone(),
+                        None => ArtemisRepo::solution_dir(*taskid)?,
+                    };
+                    ArtemisRepo::clone_solution(&url, &path, &s.git_http_auth())
+                        .context("couldn't clone the solution")?;
+                    println!("the solution is in {}", path.display());
+                }
+            }
+        }
+        Commands::Logs {
+            taskid,
+            follow,
+            participation_type,
+        } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            tokio::select! {
+                result = print_build_logs(&mut s, *taskid, *participation_type, *follow) => result?,
+                _ = tokio::signal::ctrl_c() => info!("stopped following the build logs"),
+            }
+        }
+        Commands::Team { taskid } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let team = s.get_team(*taskid).await?;
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&team)?);
+            } else {
+                print_team(&team);
+            }
+        }
+        Commands::Whoami => {
+            let account = match Adapter::init(cfg.get_timeout(), cfg).await {
+                Ok(mut s) => s.get_account().await,
+                Err(e) => Err(e),
+            };
+            match (account, cli.json) {
+                (Ok(account), true) => {
+                    let mut json = serde_json::to_value(&account)?;
+                    json["authenticated"] = true.into();
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+                (Ok(account), false) => print_account(&account),
+                (Err(e), json) => {
+                    // other errors, e.g. an unreachable server, don't tell whether the login is valid
+                    let unauthenticated = e.downcast_ref::<MissingCredentials>().is_some()
+                        || e.downcast_ref::<LoginRejected>().is_some_and(LoginRejected::unauthorized);
+                    if !unauthenticated {
+                        return Err(e);
                     }
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "authenticated": false }))?);
+                    }
+                    return Err(e.context("you are not logged in"));
+                }
+            }
+        }
+        Commands::Announcements { courseid, count } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let announcements = s.get_announcements(*courseid, *count).await?;
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&announcements)?);
+            } else {
+                print_announcements(&announcements);
+            }
+        }
+        Commands::Hints { taskid } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let hints = s.get_exercise_hints(*taskid).await?;
+            print_hints(&hints);
+        }
+        Commands::StartTask {
+            taskids,
+            participation_type,
+            dir,
+            template,
+        } => {
+            if dir.is_some() && taskids.len() > 1 {
+                bail!("--dir can only be used with a single task, the clone-dir-template config applies to several");
+            }
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            if let [taskid] = taskids.as_slice() {
+                start_task(cfg, &mut s, *taskid, *participation_type, dir.as_deref(), template.as_deref()).await?;
+                return Ok(());
+            }
+
+            // a failing task doesn't stop the others, they are all reported at the end
+            let mut outcomes = Vec::new();
+            for taskid in taskids {
+                let outcome = start_task(cfg, &mut s, *taskid, *participation_type, None, template.as_deref()).await;
+                outcomes.push((taskid, outcome));
+            }
+            let failed = outcomes.iter().filter(|(_, outcome)| outcome.is_err()).count();
+            for (taskid, outcome) in outcomes {
+                match outcome {
+                    Ok(path) => println!("{} {:<6} {}", "✓".bold().green(), taskid, path.display()),
+                    Err(e) => println!("{} {:<6} {:#}", "✗".bold().red(), taskid, e),
                 }
             }
+            if failed > 0 {
+                bail!("{} of {} tasks couldn't be started", failed, taskids.len());
+            }
         }
-        Commands::StartTask { taskid } => {
-            let mut s = Adapter::init(30, cfg.get_base_url()).await;
-            let ssh_uri = s
-                .start_artemis_task(*taskid)
-                .await
-                .expect("couldnt start the task and fetch url");
-            let repo = ArtemisRepo::create(&ssh_uri, *taskid).expect("couldn't create the repository");
-            repo.commit_and_push().expect("can't commit and push to remote repository");
-        }
-        Commands::Submit => {
-            let repo = ArtemisRepo::open(env::current_dir()?)?;
-            repo.commit_and_push()?;
-            info!("successfully submited task");
-        }
-        Commands::Fetch { taskid } => {
-            let mut s = Adapter::init(30, cfg.get_base_url()).await;
-            let test_results = s.get_latest_test_result(*taskid).await?;
-            for test_result in test_results {
-                println!(
-                    "{:<4} {} {}",
-                    if test_result.passed { "P".bold().green() } else { "F".bold().red() },
-                    test_result.name,
-                    test_result.explanation.unwrap_or("".to_string()).red(),
+        Commands::Submit {
+            output,
+            open_on_fail,
+            diff_only,
+            no_fetch,
+            resume,
+            message,
+            message_file,
+            retry_build,
+            remote,
+            tag,
+            assume_cloned,
+            task_id,
+            participation_id,
+            wait_timeout,
+            commit_author,
+        } => {
+            if let Some(author) = commit_author {
+                cfg.override_commit_author(author)?;
+            }
+            let repo_dir = repo_dir(cli)?;
+            let mut repo = if *assume_cloned {
+                ArtemisRepo::open_unchecked(&repo_dir)?
+            } else {
+                ArtemisRepo::open(&repo_dir)?
+            };
+            let taskid = match task_id {
+                Some(task_id) => *task_id,
+                None => repo.task_id()?,
+            };
+            let participation_type = repo.participation_type();
+
+            if *diff_only {
+                let patch = repo.uncommitted_diff()?;
+                if patch.is_empty() {
+                    println!("no changes since the last commit");
+                } else {
+                    print_diff(&patch);
+                }
+                if !confirm("push these changes?")? {
+                    println!("aborted, nothing was pushed");
+                    return Ok(());
+                }
+            }
+
+            // like git, every -m is a paragraph of the message
+            let message = match message_file {
+                Some(path) => {
+                    let text = fs::read_to_string(path)
+                        .with_context(|| format!("can't read the message from {}", path.display()))?;
+                    Some(text)
+                }
+                None if message.is_empty() => None,
+                None => Some(message.join("\n\n")),
+            };
+
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            repo.set_http_auth(s.git_http_auth());
+            let mut pending = if *resume {
+                match PendingResults::load()?.get(taskid, participation_type) {
+                    Some(entry) => entry.clone(),
+                    None => bail!("no submission of task {} is awaiting its result", taskid),
+                }
+            } else {
+                if !*assume_cloned {
+                    print_submission_policy(&mut s, taskid).await;
+                }
+                submit(cfg, &mut s, &repo, taskid, message.as_deref(), remote).await?
+            };
+            // the submission already arrived, so a failing tag is only worth a warning
+            if let Some(name) = tag {
+                let name = Some(name.as_str()).filter(|name| !name.is_empty());
+                match repo.tag_submission(&pending.commit, name, remote) {
+                    Ok(name) => println!("tagged the submission as {}", name),
+                    Err(e) => eprintln!("{} {:#}", "warning:".yellow().bold(), e),
+                }
+            }
+
+            if *no_fetch {
+                println!("{}", pending.commit);
+                return Ok(());
+            }
+
+            let mut retries = 0;
+            let result = loop {
+                let result = wait_for_result(
+                    &mut s,
+                    taskid,
+                    participation_type,
+                    participation_id.or_else(|| indexed_participation_id(cfg, taskid, participation_type)),
+                    &pending.commit,
+                    pending.pushed_at,
+                    Duration::from_secs(*wait_timeout),
                 )
+                .await?;
+                if !result.is_for_commit(&pending.commit, pending.pushed_at) {
+                    break result;
+                }
+                PendingResults::resolve(taskid, participation_type)?;
+                if retries == *retry_build || !result.outcome.is_infrastructure_failure() {
+                    break result;
+                }
+                retries += 1;
+                eprintln!(
+                    "{} the build failed for a reason outside of your code, submitting again ({}/{})",
+                    "note:".yellow().bold(),
+                    retries,
+                    retry_build
+                );
+                pending = submit(cfg, &mut s, &repo, taskid, message.as_deref(), remote).await?;
+            };
+            print_test_results(&result, output_options(cli), output.as_deref())?;
+
+            if *open_on_fail && result.failed() {
+                open_in_editor(cfg, &repo_dir)?;
+            }
+        }
+        Commands::Diff {
+            since_commit,
+            since_submission,
+        } => {
+            let repo = ArtemisRepo::open(repo_dir(cli)?)?;
+            let patch = match (since_commit, since_submission) {
+                (Some(revision), _) => repo.diff_since(repo.resolve_commit(revision)?)?,
+                (None, Some(n)) => repo.diff_since(repo.submission_commit(*n)?)?,
+                (None, None) => repo.uncommitted_diff()?,
+            };
+            if patch.is_empty() {
+                println!("no changes");
+            } else {
+                print_diff(&patch);
+            }
+        }
+        Commands::Pull { rebase, remote } => {
+            let mut repo = ArtemisRepo::open(repo_dir(cli)?)?;
+            if repo.uses_http(remote) {
+                repo.set_http_auth(Adapter::init(cfg.get_timeout(), cfg).await?.git_http_auth());
+            }
+            let identity = match cfg.get_commit_author().or_else(ArtemisRepo::git_identity) {
+                Some(identity) => identity,
+                None => commit_identity(cfg, &mut Adapter::init(cfg.get_timeout(), cfg).await?).await,
+            };
+
+            match repo.pull(remote, *rebase, &identity)? {
+                PullOutcome::UpToDate => println!("already up to date"),
+                PullOutcome::FastForwarded => println!("fast-forwarded to the remote branch"),
+                PullOutcome::Rebased(replayed) => println!("replayed {} local commits on top of the remote branch", replayed),
+                PullOutcome::Diverged { local, remote } => {
+                    println!("your branch and the remote branch have diverged");
+                    println!("local commits:");
+                    for commit in local {
+                        println!("  {}", commit);
+                    }
+                    println!("remote commits:");
+                    for commit in remote {
+                        println!("  {}", commit);
+                    }
+                    bail!("can't fast-forward, run 'artemis-cli pull --rebase' to replay your commits on top of the remote ones");
+                }
+            }
+        }
+        Commands::SubmitFile { taskid, file } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let submission_id = s.submit_file(*taskid, file).await?;
+            println!("successfully submitted {} (submission {})", file.display(), submission_id);
+        }
+        Commands::Fetch {
+            taskid,
+            output,
+            participation_type,
+            explain,
+            attempt,
+            participation_id,
+            result_id,
+            refresh,
+        } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            if *refresh {
+                s.bypass_result_cache();
+            }
+            let result = match participation_id {
+                Some(participation_id) => s.get_participation_test_result(*participation_id, *result_id).await?,
+                None if attempt.is_none() => {
+                    let participation_id = indexed_participation_id(cfg, *taskid, *participation_type);
+                    latest_result(&mut s, *taskid, *participation_type, participation_id).await?
+                }
+                None => s.get_test_result(*taskid, *participation_type, *attempt).await?,
+            };
+            let latest = attempt.is_none() && result_id.is_none();
+            if let Some(pending) = PendingResults::load()?.get(*taskid, *participation_type).filter(|_| latest) {
+                if result.is_for_commit(&pending.commit, pending.pushed_at) {
+                    PendingResults::resolve(*taskid, *participation_type)?;
+                } else {
+                    eprintln!(
+                        "{} the result of your submission {} isn't available yet, showing the previous result",
+                        "note:".yellow().bold(),
+                        &pending.commit[..pending.commit.len().min(7)]
+                    );
+                }
+            }
+            let options = OutputOptions {
+                explain: *explain,
+                ..output_options(cli)
+            };
+            print_test_results(&result, options, output.as_deref())?;
+        }
+        Commands::TestHistory {
+            taskid,
+            jsonl,
+            participation_type,
+        } => {
+            let mut s = Adapter::init(cfg.get_timeout(), cfg).await?;
+            let history = s.get_result_history(*taskid, *participation_type).await?;
+            print_history(&history, output_options(cli), *jsonl)?;
+        }
+        Commands::History { count, replay } => {
+            let history = CommandHistory::load()?;
+            let Some(index) = replay else {
+                for (index, invocation) in history.recent(*count).enumerate() {
+                    println!(
+                        "{:<4} {} {}",
+                        index + 1,
+                        format_date_time(&invocation.time).dimmed(),
+                        invocation.args.join(" ")
+                    );
+                }
+                return Ok(());
+            };
+
+            let invocation = history.get(*index)?;
+            let replayed = Cli::try_parse_from(env::args().take(1).chain(invocation.args.iter().cloned()))?;
+            if matches!(replayed.command, None | Some(Commands::History { .. })) {
+                bail!("command {} can't be replayed", index);
+            }
+            println!("{}", format!("replaying '{}'", invocation.args.join(" ")).dimmed());
+            Box::pin(run_commands(&replayed, cfg)).await?;
+        }
+        Commands::Schema => {
+            print_json_schemas()?;
+        }
+        Commands::Clean { repos, cache, force } => {
+            run_clean(*repos, *cache, *force)?;
+        }
+        Commands::Init => {
+            run_init(cfg, cli.cfg.as_deref()).await?;
+        }
+        Commands::Doctor => {
+            if !run_doctor(cfg).await {
+                bail!("some checks failed, see above");
             }
         }
         Commands::Config { command } => match command {
-            ConfigCommands::BaseUrl { url } => {
-                cfg.set_base_url(url.clone());
+            ConfigCommands::BaseUrl { url, university } => {
+                let url = match (url, university) {
+                    (_, Some(university)) => university_base_url(university)?.to_string(),
+                    (Some(url), None) => url.clone(),
+                    (None, None) => bail!("give either a url or --university"),
+                };
+                cfg.set_base_url(url)?;
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::UserAgent { agent } => {
+                cfg.set_user_agent(agent.clone())?;
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::AuthPath { path } => {
+                cfg.set_auth_path(path.clone());
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::Editor { command } => {
+                cfg.set_editor(command.clone());
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::Timeout { seconds } => {
+                cfg.set_timeout(*seconds)?;
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::Verbosity { level } => {
+                cfg.set_verbosity(*level);
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::TaskCacheMaxAge { hours } => {
+                cfg.set_task_cache_max_age(*hours);
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::TimeFormat { format } => {
+                cfg.set_time_format(format)?;
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::CommitAuthor { author } => {
+                cfg.set_commit_author(author)?;
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::CloneDirTemplate { template } => {
+                cfg.set_clone_dir_template(template.clone())?;
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::CredentialStore { store } => {
+                cfg.set_credential_store(*store);
+                cfg.save(cli.cfg.as_deref());
+                println!("credentials are not moved, run 'artemis-cli config username' and 'config password' again");
+            }
+            ConfigCommands::AuthMode { mode } => {
+                cfg.set_auth_mode(*mode);
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::CloneProtocol { protocol } => {
+                cfg.set_clone_protocol(*protocol);
                 cfg.save(cli.cfg.as_deref());
             }
+            ConfigCommands::RememberMe { enabled } => {
+                cfg.set_remember_me(*enabled);
+                cfg.save(cli.cfg.as_deref());
+                if !enabled {
+                    Adapter::forget_session(cfg)?;
+                }
+            }
+            ConfigCommands::Show => {
+                for (key, value, note) in cfg.entries() {
+                    println!("{:<12} {}", key, value);
+                    if let Some(note) = note {
+                        println!("{:<12} {}", "", note.dimmed());
+                    }
+                }
+            }
+            ConfigCommands::Unset { key } => {
+                cfg.unset(key)?;
+                cfg.save(cli.cfg.as_deref());
+            }
+            ConfigCommands::Migrate => {
+                let (old, new) = ArtemisConfig::migrate(cli.cfg.as_deref())?;
+                if old == new {
+                    println!("the config is already up to date");
+                } else {
+                    print_config_diff(&old, &new);
+                }
+            }
             ConfigCommands::Username { name } => {
-                let uname = Entry::new("artemiscli", "username").expect("can't create Entry for username");
-                uname.set_password(&name).expect("can't create Entry for password");
+                Credentials::new(cfg).set("username", name)?;
             }
             ConfigCommands::Password { password } => {
-                let pwd = Entry::new("artemiscli", "password").expect("can't create Entry for password");
-                pwd.set_password(&password)?;
+                Credentials::new(cfg).set("password", password)?;
             }
         },
     }
@@ -131,15 +993,39 @@ async fn run_commands(cli: &Cli, cfg: &mut ArtemisConfig) -> Result<()> {
 #[tokio::main]
 async fn main() {
     let cli: Cli = Cli::parse();
-    init_log(cli.verbosity);
-
     let mut config = ArtemisConfig::load(cli.cfg.as_deref());
 
+    // explicit -v flags take precedence over the configured default
+    let verbosity = if cli.verbosity > 0 { cli.verbosity } else { config.get_verbosity() };
+    init_log(verbosity);
+    if let Some(width) = cli.width {
+        set_output_width(width);
+    }
+    set_assume_yes(cli.yes);
+    set_time_format(config.get_time_format());
+    if cli.no_color || env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        colored::control::set_override(false);
+    }
+
     trace!("setup logging...");
 
     if cli.command.is_none() {
         warn!("command is none");
         return;
     }
-    run_commands(&cli, &mut config).await.unwrap();
+    if !matches!(cli.command, Some(Commands::History { .. }))
+        && let Err(e) = CommandHistory::record(env::args())
+    {
+        warn!("can't record the command in the history: {:#}", e);
+    }
+    if let Err(e) = run_commands(&cli, &mut config).await {
+        eprintln!("{} {:#}", "error:".bold().red(), e);
+        if e.downcast_ref::<MissingCredentials>().is_some() {
+            process::exit(EXIT_MISSING_CREDENTIALS);
+        }
+        if e.downcast_ref::<BuildStillRunning>().is_some() {
+            process::exit(EXIT_BUILD_STILL_RUNNING);
+        }
+        process::exit(1);
+    }
 }
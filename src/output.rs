@@ -0,0 +1,730 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{cmp::Ordering, fmt::Write, fs, path::Path, sync::OnceLock};
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{Local, TimeDelta};
+use clap::ValueEnum;
+use colored::{ColoredString, Colorize};
+use schemars::schema_for;
+use serde::Serialize;
+use serde_json::json;
+use terminal_size::{Width, terminal_size};
+
+use crate::{
+    core::adapter::{
+        Account, Announcement, AssessmentFeedback, Competency, Course, ExerciseHint, HistoryEntry, LogStatement,
+        StaticAnalysisIssue, Task, TaskResult, Team, Test, TestOutcome, TextFeedback,
+    },
+    pager::page,
+    time_format::format_date_time,
+};
+
+/// width of the title columns if the width of the terminal is unknown
+const DEFAULT_TITLE_WIDTH: usize = 40;
+/// titles are never cut shorter than this, even in very narrow terminals
+const MIN_TITLE_WIDTH: usize = 16;
+
+/// the width given with --width, takes precedence over the detected width of the terminal
+static OUTPUT_WIDTH: OnceLock<usize> = OnceLock::new();
+
+pub fn set_output_width(width: usize) {
+    let _ = OUTPUT_WIDTH.set(width);
+}
+
+/// the width of the title column: as wide as the longest title, but narrow enough for the rows to fit into
+/// the terminal next to `other_columns` characters of other columns
+fn title_width<'a>(titles: impl Iterator<Item = &'a str>, other_columns: usize) -> usize {
+    let width = OUTPUT_WIDTH.get().copied().or_else(|| terminal_size().map(|(Width(width), _)| width as usize));
+    let Some(width) = width else {
+        return DEFAULT_TITLE_WIDTH;
+    };
+    let longest = titles.map(|title| title.chars().count()).max().unwrap_or(0);
+    longest.min(width.saturating_sub(other_columns)).max(MIN_TITLE_WIDTH)
+}
+
+/// pads `title` to `width` characters, longer titles are shortened with an ellipsis
+fn fit(title: &str, width: usize) -> String {
+    if title.chars().count() <= width {
+        return format!("{:<width$}", title);
+    }
+    let shortened: String = title.chars().take(width.saturating_sub(1)).collect();
+    format!("{}…", shortened)
+}
+
+/// orders of the tasks printed by `print_course_tasks`, tasks missing the data are printed last
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum TaskSort {
+    /// easy tasks first
+    Difficulty,
+    /// most points per difficulty first
+    Value,
+}
+
+/// orders of the printed tests, by default they are printed in the order artemis returns them
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum TestSort {
+    /// failed tests first
+    Status,
+    /// alphabetically by name
+    Name,
+}
+
+/// which tasks of a course are printed by `print_course_tasks`
+#[derive(Clone, Debug, Default)]
+pub struct TaskListOptions {
+    /// only print the first N matching tasks
+    pub limit: Option<usize>,
+    /// only print tasks of this category, compared case-insensitively
+    pub category: Option<String>,
+    /// also print tasks that haven't been released yet
+    pub include_unreleased: bool,
+    pub sort: Option<TaskSort>,
+    /// one dense line per task instead of the columns
+    pub compact: bool,
+}
+
+impl TaskListOptions {
+    fn matches(&self, task: &Task) -> bool {
+        (self.include_unreleased || task.is_released())
+            && self.category.as_ref().is_none_or(|category| {
+                task.categories.iter().any(|task_category| task_category.eq_ignore_ascii_case(category))
+            })
+    }
+}
+
+pub(crate) fn task_status(task: &Task) -> String {
+    match task.release_date {
+        Some(release) if !task.is_released() => {
+            let hours = (release.with_timezone(&Local) - Local::now()).num_hours();
+            format!("upcoming (releases in {}d)", (hours + 23) / 24)
+        }
+        _ if task.completed => "completed".to_string(),
+        _ if task.is_active => "incomplete".to_string(),
+        _ => "not started".to_string(),
+    }
+}
+
+/// prints the id, title, semester and short name of every course
+pub fn print_courses(courses: &[Course]) {
+    let width = title_width(courses.iter().map(|course| course.title.as_str()), 26);
+    for course in courses {
+        println!(
+            "{:<5} {} {:<8} {}",
+            course.id,
+            fit(&course.title, width),
+            course.semester.as_deref().unwrap_or_default(),
+            course.short_name.as_deref().unwrap_or_default().dimmed()
+        );
+    }
+}
+
+/// prints the tasks of the course with the given id that match `options`
+pub fn print_course_tasks(courses: &[Course], courseid: u64, options: &TaskListOptions) {
+    for course in courses.iter().filter(|course| course.id == courseid) {
+        let tasks = course_tasks(course, options);
+        let total = tasks.len();
+        let shown = options.limit.unwrap_or(total).min(total);
+        // id, status and difficulty plus some space for the categories
+        let width = title_width(tasks.iter().take(shown).map(|task| task.title.as_str()), 42);
+        for task in tasks.iter().take(shown) {
+            let locked_by = course.locked_by(task);
+            if options.compact {
+                println!("{}", compact_task_line(task, !locked_by.is_empty()));
+                continue;
+            }
+            println!(
+                "{:<5} {} {:<15} {:<6} {}",
+                task.id,
+                fit(&task.title, width),
+                if locked_by.is_empty() { task_status(task) } else { "locked".to_string() },
+                task.difficulty.map(|difficulty| difficulty.to_string()).unwrap_or_default(),
+                task.categories.join(", ")
+            );
+            if !locked_by.is_empty() {
+                let titles: Vec<&str> = locked_by.iter().map(|prerequisite| prerequisite.title.as_str()).collect();
+                println!("      {}", format!("locked: complete {} first", titles.join(", ")).yellow());
+            }
+            if let Some(policy) = &task.submission_policy {
+                println!("      {}", policy.to_string().yellow());
+            }
+        }
+        if shown < total {
+            println!("(showing {} of {})", shown, total);
+        }
+    }
+}
+
+/// a task as e.g. `[✓] 1234 Title (80%, due 3d)`, without colors the status is an ASCII marker
+fn compact_task_line(task: &Task, locked: bool) -> String {
+    let color = colored::control::SHOULD_COLORIZE.should_colorize();
+    let (glyph, ascii) = match () {
+        _ if !task.is_released() => ("◷".dimmed(), "-"),
+        _ if locked => ("⊘".dimmed(), "#"),
+        _ if task.completed => ("✓".green(), "x"),
+        _ if task.is_overdue() => ("!".red(), "!"),
+        _ if task.is_active => ("◐".yellow(), "~"),
+        _ => ("○".normal(), " "),
+    };
+    let marker = if color { glyph } else { ascii.normal() };
+
+    let now = Local::now();
+    let mut details = Vec::new();
+    if let Some(score) = task.latest_score {
+        details.push(format!("{:.0}%", score));
+    }
+    match (task.release_date, task.due_date) {
+        (Some(release), _) if !task.is_released() => {
+            details.push(format!("releases in {}", short_duration(release.with_timezone(&Local) - now)))
+        }
+        (_, Some(due)) if !task.completed && due > now => {
+            details.push(format!("due {}", short_duration(due.with_timezone(&Local) - now)))
+        }
+        _ if task.is_overdue() => details.push("overdue".to_string()),
+        _ => {}
+    }
+
+    let mut line = format!("[{}] {} {}", marker, task.id, task.title);
+    if !details.is_empty() {
+        line.push_str(&format!(" ({})", details.join(", ")));
+    }
+    line
+}
+
+/// `3d` from a day on, `5h` below
+fn short_duration(duration: TimeDelta) -> String {
+    match duration.num_hours() {
+        hours if hours >= 24 => format!("{}d", hours / 24),
+        hours => format!("{}h", hours.max(0)),
+    }
+}
+
+/// the tasks of `course` that match `options` in the order given by `options`, without applying the limit
+fn course_tasks<'a>(course: &'a Course, options: &TaskListOptions) -> Vec<&'a Task> {
+    let mut tasks: Vec<&Task> = course.tasks.iter().filter(|task| options.matches(task)).collect();
+    match options.sort {
+        // None sorts before Some, so unknown difficulties are moved to the end explicitly
+        Some(TaskSort::Difficulty) => tasks.sort_by_key(|task| (task.difficulty.is_none(), task.difficulty)),
+        Some(TaskSort::Value) => tasks.sort_by(|a, b| b.value().partial_cmp(&a.value()).unwrap_or(Ordering::Equal)),
+        None => {}
+    }
+    tasks
+}
+
+/// prints the tasks `print_course_tasks` would print as a JSON array
+pub fn print_course_tasks_json(courses: &[Course], courseid: u64, options: &TaskListOptions) -> Result<()> {
+    let course = courses
+        .iter()
+        .find(|course| course.id == courseid)
+        .ok_or(anyhow!("you are not enrolled in a course with id {}", courseid))?;
+    let tasks = course_tasks(course, options);
+    let shown = options.limit.unwrap_or(tasks.len()).min(tasks.len());
+    println!("{}", serde_json::to_string_pretty(&tasks[..shown])?);
+    Ok(())
+}
+
+/// prints JSON schemas of the courses, tasks and tests printed with --json
+pub fn print_json_schemas() -> Result<()> {
+    let schemas = json!({
+        "Course": schema_for!(Course),
+        "Task": schema_for!(Task),
+        "Test": schema_for!(Test),
+    });
+    println!("{}", serde_json::to_string_pretty(&schemas)?);
+    Ok(())
+}
+
+pub fn print_next_task(course: &Course) {
+    match course.next_task() {
+        Some(task) => println!(
+            "{:<5} {} due {}",
+            task.id,
+            fit(&task.title, title_width([task.title.as_str()].into_iter(), 27)),
+            format_date_time(&task.due_date.unwrap())
+        ),
+        None => println!("nothing pending in {}", course.title),
+    }
+}
+
+/// prints the number of courses, exercises, completed and overdue exercises over all courses
+pub fn print_course_totals(courses: &[Course]) {
+    let tasks = || courses.iter().flat_map(|course| course.tasks.iter());
+    println!("{:<10} {}", "courses", courses.len());
+    println!("{:<10} {}", "exercises", tasks().count());
+    println!("{:<10} {}", "completed", tasks().filter(|task| task.completed).count());
+    println!("{:<10} {}", "overdue", tasks().filter(|task| task.is_overdue()).count());
+}
+
+/// renders `percent` as a bar of `width` characters, e.g. `████░░░░`
+pub fn progress_bar(percent: f64, width: usize) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * width as f64).round() as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// colors `bar` green from 80%, yellow from 50% and red below
+fn color_by_score(bar: String, percent: f64) -> ColoredString {
+    match percent {
+        p if p >= 80.0 => bar.green(),
+        p if p >= 50.0 => bar.yellow(),
+        _ => bar.red(),
+    }
+}
+
+/// prints the latest score of every released task and the total of the course weighted by the points of
+/// the tasks. without colors only the percentages are printed
+pub fn print_grades(courses: &[Course], courseid: u64) -> Result<()> {
+    let Some(course) = courses.iter().find(|course| course.id == courseid) else {
+        bail!("you are not enrolled in a course with id {}", courseid);
+    };
+    let color = colored::control::SHOULD_COLORIZE.should_colorize();
+    let total_label = format!("total ({})", course.title);
+    let labels = course.tasks.iter().map(|task| task.title.as_str()).chain([total_label.as_str()]);
+    let width = title_width(labels, if color { 29 } else { 8 });
+    let row = |label: &str, percent: Option<f64>| match percent {
+        Some(percent) if color => {
+            let bar = color_by_score(progress_bar(percent, 20), percent);
+            println!("{} {} {:>5.1}%", fit(label, width), bar, percent)
+        }
+        Some(percent) => println!("{} {:>5.1}%", fit(label, width), percent),
+        None => println!("{} {}", fit(label, width), "no result".dimmed()),
+    };
+
+    let (mut achieved, mut reachable) = (0.0, 0.0);
+    for task in course.tasks.iter().filter(|task| task.is_released()) {
+        row(&task.title, task.latest_score);
+        let points = task.max_points.unwrap_or(1.0);
+        achieved += task.latest_score.unwrap_or(0.0) * points;
+        reachable += points;
+    }
+
+    println!();
+    let total = (reachable > 0.0).then(|| achieved / reachable);
+    row(&total_label, total);
+    Ok(())
+}
+
+/// prints the used and remaining submissions of every task of a course with a submission policy, highlighting the
+/// tasks that can still be improved before the due date
+pub fn print_attempts(courses: &[Course], courseid: u64) -> Result<()> {
+    let Some(course) = courses.iter().find(|course| course.id == courseid) else {
+        bail!("you are not enrolled in a course with id {}", courseid);
+    };
+    let tasks: Vec<&Task> = course.tasks.iter().filter(|task| task.submission_policy.is_some()).collect();
+    if tasks.is_empty() {
+        println!("no task of {} limits the number of submissions", course.title);
+        return Ok(());
+    }
+
+    let width = title_width(tasks.iter().map(|task| task.title.as_str()), 36);
+    let mut improvable = 0;
+    for task in tasks {
+        let (Some(policy), Some(remaining)) = (task.submission_policy, task.remaining_attempts()) else { continue };
+        let score = task.latest_score.map_or("no result".to_string(), |score| format!("{:.1}%", score));
+        let row = format!(
+            "{:<6} {} {:>3}/{:<3} used {:>3} left  {}",
+            task.id,
+            fit(&task.title, width),
+            task.result_count,
+            policy.limit(),
+            remaining,
+            score
+        );
+        if remaining > 0 && !task.completed && !task.is_overdue() {
+            improvable += 1;
+            println!("{}", row.yellow());
+        } else {
+            println!("{}", row);
+        }
+    }
+
+    if improvable > 0 {
+        println!();
+        println!("you could still improve {} task(s) with the attempts you have left", improvable);
+    }
+    Ok(())
+}
+
+/// prints the lines of a config file that were removed and added by a migration
+pub fn print_config_diff(old: &str, new: &str) {
+    for line in old.lines().filter(|line| !new.lines().any(|new_line| new_line == *line)) {
+        println!("{}", format!("-{}", line).red());
+    }
+    for line in new.lines().filter(|line| !old.lines().any(|old_line| old_line == *line)) {
+        println!("{}", format!("+{}", line).green());
+    }
+}
+
+pub fn print_competencies(competencies: &[Competency]) {
+    if competencies.is_empty() {
+        println!("no competencies configured");
+        return;
+    }
+
+    let width = title_width(competencies.iter().map(|competency| competency.title.as_str()), 35);
+    for competency in competencies {
+        let bar = progress_bar(competency.progress, 20);
+        let mastered = competency.mastery_threshold.is_some_and(|threshold| competency.progress >= threshold);
+        println!(
+            "{:<5} {} {} {:>5.1}%",
+            competency.id,
+            fit(&competency.title, width),
+            if mastered { bar.green() } else { bar.normal() },
+            competency.progress
+        );
+    }
+}
+
+pub fn print_account(account: &Account) {
+    println!("{} ({})", account.login.bold(), account.name);
+    println!("{}", account.email);
+}
+
+pub fn print_team(team: &Team) {
+    println!("{} ({})", team.name.bold(), team.short_name);
+    for member in &team.members {
+        println!("  {:<12} {}", member.login, member.name);
+    }
+    match &team.repository {
+        Some(repository) => println!("repository: {}", repository),
+        None => println!("{}", "your team hasn't started the task yet".dimmed()),
+    }
+}
+
+pub fn print_announcements(announcements: &[Announcement]) {
+    if announcements.is_empty() {
+        println!("this course has no announcements");
+        return;
+    }
+
+    for announcement in announcements {
+        let date = announcement
+            .date
+            .map(|date| format_date_time(&date))
+            .unwrap_or_default();
+        println!(
+            "{} {} {}",
+            announcement.title.as_deref().unwrap_or("(no title)").bold(),
+            announcement.author.as_deref().unwrap_or_default(),
+            date.dimmed()
+        );
+        for line in announcement.content.lines() {
+            println!("    {}", line);
+        }
+        println!();
+    }
+}
+
+pub fn print_hints(hints: &[ExerciseHint]) {
+    if hints.is_empty() {
+        println!("this task has no hints");
+        return;
+    }
+
+    for hint in hints {
+        match &hint.content {
+            Some(content) => {
+                println!("{}", hint.title.bold());
+                for line in content.lines() {
+                    println!("    {}", line);
+                }
+            }
+            None if hint.available => println!("{}", hint.title.bold()),
+            None => println!("{} {}", hint.title.bold(), "(not available yet)".dimmed()),
+        }
+    }
+}
+
+/// options controlling how test results are rendered
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OutputOptions {
+    /// render JSON instead of a plain text report
+    pub json: bool,
+    /// only render the summary
+    pub quiet: bool,
+    /// leave out the tests that passed
+    pub only_failed: bool,
+    /// include textual feedback, e.g. from a tutor
+    pub explain: bool,
+    pub sort_tests: Option<TestSort>,
+    /// show long reports in $PAGER
+    pub pager: bool,
+}
+
+/// the tests that should be rendered, in the order they should be rendered in
+fn shown_tests(tests: &[Test], options: OutputOptions) -> Vec<&Test> {
+    let mut tests: Vec<&Test> = tests.iter().filter(|test| !options.only_failed || !test.passed).collect();
+    match options.sort_tests {
+        // the sort is stable, so the server order is kept within both groups
+        Some(TestSort::Status) => tests.sort_by_key(|test| test.passed),
+        Some(TestSort::Name) => tests.sort_by(|a, b| a.name.cmp(&b.name)),
+        None => {}
+    }
+    tests
+}
+
+/// aggregated numbers of a result so scripts don't have to compute them from the tests
+#[derive(Clone, Debug, Serialize)]
+struct Summary {
+    passed: usize,
+    total: usize,
+    score: Option<f64>,
+    build_failed: bool,
+}
+
+impl Summary {
+    fn new(result: &TaskResult) -> Self {
+        match &result.outcome {
+            TestOutcome::TestResults(tests) => Self {
+                passed: tests.iter().filter(|test| test.passed).count(),
+                total: tests.len(),
+                score: result.score,
+                build_failed: false,
+            },
+            TestOutcome::BuildFailure(_) => Self {
+                passed: 0,
+                total: 0,
+                score: result.score,
+                build_failed: true,
+            },
+        }
+    }
+}
+
+/// renders the test results either as a plain text report or as JSON.
+/// `color` only affects the plain text report and should be false when writing to a file.
+pub fn render_test_results(result: &TaskResult, options: OutputOptions, color: bool) -> Result<String> {
+    let summary = Summary::new(result);
+    if options.json {
+        let json = match &result.outcome {
+            _ if options.quiet => serde_json::to_value(&summary)?,
+            TestOutcome::TestResults(tests) => {
+                let tests = shown_tests(tests, options);
+                let mut json = json!({
+                    "summary": summary,
+                    "tests": tests,
+                    "static_analysis": result.static_analysis,
+                    "assessment": result.assessment,
+                });
+                if options.explain {
+                    json["feedback"] = serde_json::to_value(&result.feedback)?;
+                }
+                json
+            }
+            TestOutcome::BuildFailure(logs) => json!({ "summary": summary, "logs": logs }),
+        };
+        return Ok(serde_json::to_string_pretty(&json)?);
+    }
+
+    let mut out = String::new();
+    if !options.quiet {
+        match &result.outcome {
+            TestOutcome::TestResults(tests) => out.push_str(&render_tests(tests, options, color)?),
+            TestOutcome::BuildFailure(logs) => out.push_str(&render_build_failure(logs, color)?),
+        }
+        if !result.static_analysis.is_empty() {
+            out.push_str(&render_static_analysis(&result.static_analysis, color)?);
+        }
+        if !result.assessment.is_empty() {
+            out.push_str(&render_assessment(&result.assessment, color)?);
+        }
+    }
+    if options.explain {
+        out.push_str(&render_feedback(&result.feedback, color)?);
+    }
+    out.push_str(&render_summary(&summary));
+    Ok(out)
+}
+
+fn render_summary(summary: &Summary) -> String {
+    let score = summary.score.map(|score| format!("{:.1}%", score)).unwrap_or("n/a".to_string());
+    if summary.build_failed {
+        format!("build failed, score {}\n", score)
+    } else {
+        format!("{} of {} tests passed, score {}\n", summary.passed, summary.total, score)
+    }
+}
+
+fn render_tests(tests: &[Test], options: OutputOptions, color: bool) -> Result<String> {
+    let mut out = String::new();
+    for test_result in shown_tests(tests, options) {
+        let mut status = if test_result.passed { "P".bold().green() } else { "F".bold().red() };
+        let mut explanation = test_result.explanation.as_deref().unwrap_or("").red();
+        if !color {
+            status = status.clear();
+            explanation = explanation.clear();
+        }
+        writeln!(out, "{:<4} {} {}", status, test_result.name, explanation)?;
+    }
+    if options.only_failed {
+        let hidden = tests.iter().filter(|test| test.passed).count();
+        if hidden > 0 {
+            writeln!(out, "{} passed (hidden)", hidden)?;
+        }
+    }
+    Ok(out)
+}
+
+fn render_static_analysis(issues: &[StaticAnalysisIssue], color: bool) -> Result<String> {
+    let mut out = String::new();
+    let heading = format!("STATIC CODE ANALYSIS ({} issues):", issues.len());
+    writeln!(out, "\n{}", if color { heading.yellow().bold() } else { heading.normal() })?;
+    for issue in issues {
+        let location = match issue.line {
+            Some(line) => format!("{}:{}", issue.file, line),
+            None => issue.file.clone(),
+        };
+        writeln!(out, "{} [{}] {}", location, issue.rule, issue.message)?;
+    }
+    Ok(out)
+}
+
+fn render_assessment(assessment: &[AssessmentFeedback], color: bool) -> Result<String> {
+    let mut out = String::new();
+    let heading = "TUTOR ASSESSMENT:";
+    writeln!(out, "\n{}", if color { heading.magenta().bold() } else { heading.normal() })?;
+    for entry in assessment {
+        let text = entry.points.map(|points| format!("{:+}", points)).unwrap_or_default();
+        let points = match entry.points {
+            Some(points) if color && points < 0.0 => text.red(),
+            Some(_) if color => text.green(),
+            _ => text.normal(),
+        };
+        writeln!(out, "{:>6} {}", points, entry.criterion)?;
+        for line in entry.comment.iter().flat_map(|comment| comment.lines()) {
+            writeln!(out, "       {}", line)?;
+        }
+    }
+    writeln!(out)?;
+    Ok(out)
+}
+
+fn render_feedback(feedback: &[TextFeedback], color: bool) -> Result<String> {
+    let mut out = String::new();
+    let heading = "FEEDBACK:";
+    writeln!(out, "\n{}", if color { heading.cyan().bold() } else { heading.normal() })?;
+    if feedback.is_empty() {
+        writeln!(out, "no textual feedback for this result")?;
+    }
+    for entry in feedback {
+        let title = entry.title.as_deref().unwrap_or(&entry.kind);
+        writeln!(out, "{}", if color { title.bold() } else { title.normal() })?;
+        for line in entry.text.lines() {
+            writeln!(out, "    {}", line)?;
+        }
+    }
+    writeln!(out)?;
+    Ok(out)
+}
+
+fn render_build_failure(logs: &[LogStatement], color: bool) -> Result<String> {
+    let mut out = String::new();
+    if color {
+        writeln!(out, "{}", "BUILD FAILURE:".red().bold())?;
+        for log in logs {
+            writeln!(out, "{}", log)?;
+        }
+    } else {
+        writeln!(out, "BUILD FAILURE:")?;
+        for log in logs {
+            writeln!(out, "{:<8} {}", log.timestamp(), log.log)?;
+        }
+    }
+    Ok(out)
+}
+
+/// prints the results of a participation as a table, a JSON array or one JSON object per line.
+/// with `quiet` only the latest result is printed.
+pub fn print_history(history: &[HistoryEntry], options: OutputOptions, jsonl: bool) -> Result<()> {
+    let entries = if options.quiet { &history[history.len().saturating_sub(1)..] } else { history };
+
+    if jsonl {
+        for entry in entries {
+            println!("{}", serde_json::to_string(entry)?);
+        }
+        return Ok(());
+    }
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("there are no results yet");
+    }
+    for entry in entries {
+        let tests = match (entry.passed, entry.total) {
+            (Some(passed), Some(total)) => format!("{}/{}", passed, total),
+            _ => "n/a".to_string(),
+        };
+        let score = entry.score.map(|score| format!("{:.1}%", score)).unwrap_or("n/a".to_string());
+        println!(
+            "{:<19} {:>7} {:>9}",
+            format_date_time(&entry.date),
+            score,
+            tests
+        );
+    }
+    Ok(())
+}
+
+/// prints a patch colored like `git diff`
+pub fn print_diff(patch: &str) {
+    for line in patch.lines() {
+        if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") {
+            println!("{}", line.bold());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// prints the test results to stdout and, if a path is given, also writes them to that file
+pub fn print_test_results(result: &TaskResult, options: OutputOptions, output: Option<&Path>) -> Result<()> {
+    let mut report = render_test_results(result, options, true)?;
+    if options.json {
+        report.push('\n');
+    }
+    let paged = options.pager && !options.json && page(&report)?;
+    if !paged {
+        print!("{}", report);
+    }
+
+    if let Some(path) = output {
+        let mut report = render_test_results(result, options, false)?;
+        if options.json {
+            report.push('\n');
+        }
+        write_output_file(path, &report)?;
+    }
+    Ok(())
+}
+
+fn write_output_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty())
+        && !parent.is_dir()
+    {
+        bail!("can't write results to {}: directory {} does not exist", path.display(), parent.display());
+    }
+    fs::write(path, content).with_context(|| format!("can't write results to {}", path.display()))
+}
@@ -0,0 +1,67 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+use anyhow::Result;
+use log::info;
+use terminal_size::{Height, terminal_size};
+
+/// used if $PAGER isn't set, -R keeps the colors
+const DEFAULT_PAGER: &str = "less -R";
+
+/// shows `text` in $PAGER if it doesn't fit on the terminal. nothing is shown and false returned if stdout isn't
+/// a terminal, colors are disabled, the text fits or the pager can't be started, the caller prints it then
+pub fn page(text: &str) -> Result<bool> {
+    if !io::stdout().is_terminal() || !colored::control::SHOULD_COLORIZE.should_colorize() {
+        return Ok(false);
+    }
+    let Some((_, Height(height))) = terminal_size() else {
+        return Ok(false);
+    };
+    if text.lines().count() < usize::from(height) {
+        return Ok(false);
+    }
+
+    let pager = env::var("PAGER").ok().filter(|pager| !pager.trim().is_empty());
+    let pager = pager.as_deref().unwrap_or(DEFAULT_PAGER);
+    // the pager may be given with arguments like the editor
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else {
+        return Ok(false);
+    };
+    let mut child = match Command::new(program).args(parts).stdin(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            info!("can't start pager '{}': {}", pager, e);
+            return Ok(false);
+        }
+    };
+
+    // the pager may be quit before it read everything, that isn't an error
+    if let Some(mut stdin) = child.stdin.take()
+        && let Err(e) = stdin.write_all(text.as_bytes())
+    {
+        info!("the pager stopped reading: {}", e);
+    }
+    child.wait()?;
+    Ok(true)
+}
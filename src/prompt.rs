@@ -0,0 +1,85 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::{Result, bail};
+use log::warn;
+
+/// set by --yes, every confirmation is answered with yes without asking
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// asks a yes/no question on the terminal, defaults to no and never asks when stdin isn't a terminal.
+/// with --yes the question is answered with yes, but still printed so scripted runs show what they agreed to
+pub fn confirm(question: &str) -> Result<bool> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        eprintln!("{} [y/N] yes (--yes)", question);
+        warn!("answered '{}' with yes because of --yes", question);
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// asks for a line of input, an empty answer selects `default` if there is one
+pub fn ask(question: &str, default: Option<&str>) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        bail!("can't ask for {}, stdin is not a terminal", question);
+    }
+
+    loop {
+        match default {
+            Some(default) => print!("{} [{}]: ", question, default),
+            None => print!("{}: ", question),
+        }
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        // stdin was closed, asking again would never get an answer
+        if io::stdin().lock().read_line(&mut answer)? == 0 {
+            bail!("no answer given for {}", question);
+        }
+        match (answer.trim(), default) {
+            ("", Some(default)) => return Ok(default.to_string()),
+            ("", None) => continue,
+            (answer, _) => return Ok(answer.to_string()),
+        }
+    }
+}
+
+/// reads a secret from the terminal without echoing it
+pub fn read_secret(prompt: &str) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        bail!("can't ask for {}, stdin is not a terminal", prompt.trim_end_matches([':', ' ']));
+    }
+    Ok(rpassword::prompt_password(prompt)?)
+}
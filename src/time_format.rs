@@ -0,0 +1,110 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{fmt::Display, str::FromStr, sync::OnceLock};
+
+use anyhow::{Error, Result, bail};
+use chrono::{
+    DateTime, Local, TimeDelta, TimeZone,
+    format::{Item, StrftimeItems},
+};
+
+/// the configured time format, 24h until `set_time_format` is called
+static TIME_FORMAT: OnceLock<TimeFormat> = OnceLock::new();
+
+/// how timestamps are rendered, set with `config time-format`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TimeFormat {
+    /// e.g. 2025-04-01 14:30
+    #[default]
+    H24,
+    /// e.g. 2025-04-01 02:30 PM
+    H12,
+    /// e.g. in 3d or 5m ago, times of day in log output fall back to 24h
+    Relative,
+    /// a strftime pattern, e.g. %d.%m.%Y %H:%M
+    Pattern(String),
+}
+
+impl FromStr for TimeFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "24h" => Ok(TimeFormat::H24),
+            "12h" => Ok(TimeFormat::H12),
+            "relative" => Ok(TimeFormat::Relative),
+            "" => bail!("the time format can't be empty"),
+            pattern if StrftimeItems::new(pattern).any(|item| item == Item::Error) => {
+                bail!("'{}' is neither 12h, 24h, relative nor a valid strftime pattern", pattern)
+            }
+            pattern => Ok(TimeFormat::Pattern(pattern.to_string())),
+        }
+    }
+}
+
+impl Display for TimeFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeFormat::H24 => write!(f, "24h"),
+            TimeFormat::H12 => write!(f, "12h"),
+            TimeFormat::Relative => write!(f, "relative"),
+            TimeFormat::Pattern(pattern) => write!(f, "{}", pattern),
+        }
+    }
+}
+
+pub fn set_time_format(format: TimeFormat) {
+    let _ = TIME_FORMAT.set(format);
+}
+
+fn time_format() -> &'static TimeFormat {
+    TIME_FORMAT.get_or_init(TimeFormat::default)
+}
+
+/// `time` in the local timezone as date and time, in the configured format
+pub fn format_date_time<Tz: TimeZone>(time: &DateTime<Tz>) -> String {
+    let time = time.with_timezone(&Local);
+    match time_format() {
+        TimeFormat::H24 => time.format("%Y-%m-%d %H:%M").to_string(),
+        TimeFormat::H12 => time.format("%Y-%m-%d %I:%M %p").to_string(),
+        TimeFormat::Relative => relative(time - Local::now()),
+        TimeFormat::Pattern(pattern) => time.format(pattern).to_string(),
+    }
+}
+
+/// `time` in the local timezone as time of day, in the configured format, used for log lines
+pub fn format_time_of_day<Tz: TimeZone>(time: &DateTime<Tz>) -> String {
+    let time = time.with_timezone(&Local);
+    match time_format() {
+        TimeFormat::H24 | TimeFormat::Relative => time.format("%H:%M:%S").to_string(),
+        TimeFormat::H12 => time.format("%I:%M:%S %p").to_string(),
+        TimeFormat::Pattern(pattern) => time.format(pattern).to_string(),
+    }
+}
+
+/// e.g. "in 3d" for a positive and "5m ago" for a negative offset from now
+fn relative(offset: TimeDelta) -> String {
+    let seconds = offset.num_seconds().abs();
+    let amount = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    };
+    if offset > TimeDelta::zero() { format!("in {}", amount) } else { format!("{} ago", amount) }
+}
@@ -0,0 +1,94 @@
+/*
+Copyright (C) 2025 Niklas Liesch <niklas.liesch@protonmail.com>
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use anyhow::Result;
+use chrono::Local;
+use log::{info, warn};
+use notify_rust::Notification;
+
+use crate::{
+    core::adapter::{Adapter, Course},
+    time_format::format_date_time,
+};
+
+/// what has been seen in earlier polls, so every event is only notified once
+#[derive(Default)]
+struct WatchState {
+    /// tasks that were already reported as due soon
+    due_soon: HashSet<u64>,
+    /// the number of results per task in the last poll
+    result_counts: HashMap<u64, usize>,
+}
+
+impl WatchState {
+    /// compares the courses with the last poll and returns the notifications to send as (summary, body)
+    fn update(&mut self, courses: &[Course], due_soon: chrono::Duration) -> Vec<(String, String)> {
+        let first_poll = self.result_counts.is_empty();
+        let now = Local::now();
+        let mut notifications = Vec::new();
+
+        for course in courses {
+            for task in &course.tasks {
+                let previous = self.result_counts.insert(task.id, task.result_count);
+                if !first_poll && previous.is_some_and(|previous| previous < task.result_count) {
+                    let score = task.latest_score.map_or("n/a".to_string(), |score| format!("{:.1}%", score));
+                    notifications.push((format!("new result for {}", task.title), format!("score {}", score)));
+                }
+
+                let Some(due) = task.due_date.map(|due| due.with_timezone(&Local)) else { continue };
+                let pending = !task.completed && task.is_released() && due > now;
+                if pending && due - now <= due_soon && self.due_soon.insert(task.id) {
+                    notifications.push((
+                        format!("{} is due soon", task.title),
+                        format!("{} is due {}", course.title, format_date_time(&due)),
+                    ));
+                }
+            }
+        }
+        notifications
+    }
+}
+
+/// polls the dashboard every `interval` and sends desktop notifications for tasks that are due within
+/// `due_soon` and for new results. runs until an error occurs, stop it by cancelling the future.
+pub async fn run_watch(s: &mut Adapter, interval: Duration, due_soon: Duration) -> Result<()> {
+    let due_soon = chrono::Duration::from_std(due_soon)?;
+    let mut state = WatchState::default();
+    loop {
+        // a failed fetch, e.g. while offline, is tried again at the next interval instead of ending the watch
+        let courses = match s.get_all_courses().await {
+            Ok(courses) => courses,
+            Err(e) => {
+                warn!("can't fetch your courses, trying again in {}s: {:#}", interval.as_secs(), e);
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+        };
+        for (summary, body) in state.update(&courses, due_soon) {
+            info!("{}: {}", summary, body);
+            if let Err(e) = Notification::new().appname("artemis-cli").summary(&summary).body(&body).show() {
+                warn!("can't show notification '{}': {}", summary, e);
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}